@@ -0,0 +1,65 @@
+use macroquad::prelude::*;
+use mqanim::{camera::CameraView, easing::Easing, map, plot::Graph, Animation};
+use std::f32::consts::PI;
+
+const WINDOW_WIDTH: f32 = 1280.0;
+const WINDOW_HEIGHT: f32 = 720.0;
+fn window_conf() -> Conf {
+    Conf {
+        window_title: "Camera Example".to_owned(),
+        sample_count: 16,
+        window_width: WINDOW_WIDTH as i32,
+        window_height: WINDOW_HEIGHT as i32,
+        ..Default::default()
+    }
+}
+
+#[macroquad::main(window_conf)]
+async fn main() {
+    let mut animation = Animation::new(WINDOW_WIDTH, WINDOW_HEIGHT, None);
+
+    let graph = Graph::new(
+        vec2(0., 0.),
+        vec2(WINDOW_WIDTH - 100., WINDOW_HEIGHT - 100.),
+        -3.5..3.5,
+        -3.5..3.5,
+    );
+    let sine: Vec<Vec2> = (-200..200)
+        .map(|val| {
+            let t = map(val as f32, -200.0, 200.0, -PI, PI);
+            vec2(t, f32::sin(t * 4.) / t)
+        })
+        .collect();
+
+    animation.on_draw(move |animation| {
+        graph.draw_axes();
+        graph.plot_line_vec(&sine, 3., PURPLE);
+
+        // The mouse stays in world coordinates however the camera moves
+        let mouse = animation.get_world_mouse();
+        draw_circle(mouse.x, mouse.y, 5. / animation.view().zoom, ORANGE);
+    });
+
+    let peak = CameraView::fit(Rect::new(-80., -20., 160., 200.), animation.size())
+        .expect("The peak has an area");
+    loop {
+        animation.wait(1.).await;
+        animation
+            .animate_view(peak, 2., Easing::CubicInOut)
+            .await
+            .expect("Invalid camera view");
+        animation.wait(1.).await;
+        animation
+            .animate_view(
+                CameraView::new(vec2(0., 0.), 1.5, 20.),
+                2.,
+                Easing::CubicInOut,
+            )
+            .await
+            .expect("Invalid camera view");
+        animation
+            .animate_view(CameraView::default(), 2., Easing::CubicInOut)
+            .await
+            .expect("Invalid camera view");
+    }
+}
@@ -0,0 +1,56 @@
+use macroquad::prelude::*;
+use mqanim::{export::Export, motion_blur::MotionBlur, scaling::RenderResolution, Animation};
+
+const WINDOW_WIDTH: f32 = 1280.0;
+const WINDOW_HEIGHT: f32 = 720.0;
+fn window_conf() -> Conf {
+    Conf {
+        window_title: "Export Example".to_owned(),
+        sample_count: 16,
+        window_width: WINDOW_WIDTH as i32,
+        window_height: WINDOW_HEIGHT as i32,
+        ..Default::default()
+    }
+}
+
+#[macroquad::main(window_conf)]
+async fn main() {
+    let mut animation = Animation::new(WINDOW_WIDTH, WINDOW_HEIGHT, None);
+    // Add `transparent` to the arguments to keep the alpha channel in the exported frames
+    if std::env::args().any(|arg| arg == "transparent") {
+        animation.set_transparent(true);
+    }
+    // Run with `cargo run --example export -- gif` to write a looping gif instead of pngs
+    let export = if std::env::args().any(|arg| arg == "gif") {
+        Export::gif("export_frames/circle.gif")
+            .frame_range(0..126)
+            .frame_delay(0.03)
+    } else {
+        // The pngs are 1080p while the drawing code keeps using the 1280x720 world coordinates
+        animation.set_render_resolution(RenderResolution::Fixed {
+            width: 1920,
+            height: 1080,
+        });
+        Export::png_sequence("export_frames").frame_limit(120)
+    };
+    // `blur` averages 8 sub frames over half of each frame so the fast circle doesn't strobe
+    let export = if std::env::args().any(|arg| arg == "blur") {
+        export.motion_blur(MotionBlur::new(8, 180.).expect("Invalid motion blur"))
+    } else {
+        export
+    };
+    animation
+        .start_export(export)
+        .expect("Failed to start the export");
+
+    loop {
+        animation.set_camera();
+        let time = animation.time() * 3.;
+        draw_circle(200. * f32::cos(time), 200. * f32::sin(time), 30., ORANGE);
+
+        animation.set_default_camera();
+        animation.draw_frame();
+
+        next_frame().await;
+    }
+}
@@ -0,0 +1,79 @@
+use macroquad::prelude::*;
+use mqanim::{
+    inset::{Inset, InsetStyle},
+    Animation,
+};
+use std::f32::consts::TAU;
+
+const WINDOW_WIDTH: f32 = 1280.0;
+const WINDOW_HEIGHT: f32 = 720.0;
+fn window_conf() -> Conf {
+    Conf {
+        window_title: "Inset Example".to_owned(),
+        sample_count: 16,
+        window_width: WINDOW_WIDTH as i32,
+        window_height: WINDOW_HEIGHT as i32,
+        ..Default::default()
+    }
+}
+
+#[macroquad::main(window_conf)]
+async fn main() {
+    let mut animation = Animation::new(WINDOW_WIDTH, WINDOW_HEIGHT, None);
+    animation
+        .add_inset(
+            "zoom",
+            Inset::new(
+                Rect::new(-60., -40., 120., 80.),
+                Rect::new(240., 60., 360., 240.),
+            )
+            .style(InsetStyle {
+                border_color: YELLOW,
+                connector_color: Color::new(1., 1., 0., 0.5),
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+    loop {
+        let time = animation.time();
+        // The source drifts and the inset follows it
+        if let Some(inset) = animation.inset_mut("zoom") {
+            inset.set_source(Rect::new(
+                -60. + 20. * f32::sin(time * 0.5),
+                -40.,
+                120.,
+                80.,
+            ));
+        }
+
+        animation.set_camera();
+        draw_scene(time);
+        // The inset is a second view of the same scene
+        animation.begin_inset("zoom").unwrap();
+        draw_scene(time);
+        animation.end_inset();
+
+        animation.set_default_camera();
+        animation.draw_frame();
+
+        next_frame().await;
+    }
+}
+
+fn draw_scene(time: f32) {
+    for x in (-640..=640).step_by(20) {
+        draw_line(x as f32, -360., x as f32, 360., 1., DARKGRAY);
+    }
+    for y in (-360..=360).step_by(20) {
+        draw_line(-640., y as f32, 640., y as f32, 1., DARKGRAY);
+    }
+
+    // Small details that only read in the inset
+    for i in 0..12 {
+        let angle = time + i as f32 / 12. * TAU;
+        let position = vec2(f32::cos(angle), f32::sin(angle)) * 25.;
+        draw_circle(position.x, position.y, 3., ORANGE);
+    }
+    draw_circle(-300., 0., 80., BLUE);
+}
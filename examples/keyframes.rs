@@ -0,0 +1,66 @@
+use macroquad::prelude::*;
+use mqanim::{easing::Easing, keyframe::Track, playback::PlaybackControls, plot::Graph, Animation};
+use std::ops::Range;
+
+const WINDOW_WIDTH: f32 = 1280.0;
+const WINDOW_HEIGHT: f32 = 720.0;
+fn window_conf() -> Conf {
+    Conf {
+        window_title: "Keyframes Example".to_owned(),
+        sample_count: 16,
+        window_width: WINDOW_WIDTH as i32,
+        window_height: WINDOW_HEIGHT as i32,
+        ..Default::default()
+    }
+}
+
+const X_RANGE_TRACK: &str = "
+# time  start end  easing
+0.0     -4    4    cubic_in_out
+2.0     -1    1    linear
+4.0     -1    1    cubic_bezier(0.68, -0.6, 0.32, 1.6)
+6.0     -4    4
+";
+
+#[macroquad::main(window_conf)]
+async fn main() {
+    let mut animation = Animation::new(WINDOW_WIDTH, WINDOW_HEIGHT, None);
+
+    let x_range: Track<Range<f32>> =
+        Track::parse(X_RANGE_TRACK).expect("The x range track is invalid");
+    let waypoints = Track::new()
+        .keyframe_eased(0., vec2(-3., 0.), Easing::SineInOut)
+        .keyframe_eased(1.5, vec2(-1., 2.), Easing::SineInOut)
+        .keyframe_eased(3., vec2(1., -2.), Easing::SineInOut)
+        .keyframe(4.5, vec2(3., 0.));
+    let colors = Track::new()
+        .keyframe(0., ORANGE)
+        .keyframe(3., ORANGE)
+        .keyframe(3.5, SKYBLUE);
+
+    // Space pauses, the arrow keys step frames and the timeline can be scrubbed with the mouse
+    animation.enable_playback_controls(PlaybackControls::new().duration(x_range.duration()));
+
+    loop {
+        animation.set_camera();
+        let time = animation.time() % x_range.duration();
+
+        let graph = Graph::new(
+            vec2(0., 0.),
+            vec2(WINDOW_WIDTH - 100., WINDOW_HEIGHT - 100.),
+            x_range.sample(time).unwrap(),
+            -3.5..3.5,
+        );
+        graph.draw_axes();
+        graph.plot_pt_vec(
+            &waypoints.sample(time).unwrap(),
+            10.,
+            colors.sample(time).unwrap(),
+        );
+
+        animation.set_default_camera();
+        animation.draw_frame();
+
+        next_frame().await;
+    }
+}
@@ -0,0 +1,68 @@
+use macroquad::prelude::*;
+use mqanim::{blend::BlendMode, ui::draw_text_centered, Animation};
+
+const WINDOW_WIDTH: f32 = 1280.0;
+const WINDOW_HEIGHT: f32 = 720.0;
+fn window_conf() -> Conf {
+    Conf {
+        window_title: "Layers Example".to_owned(),
+        sample_count: 16,
+        window_width: WINDOW_WIDTH as i32,
+        window_height: WINDOW_HEIGHT as i32,
+        ..Default::default()
+    }
+}
+
+#[macroquad::main(window_conf)]
+async fn main() {
+    let mut animation = Animation::new(WINDOW_WIDTH, WINDOW_HEIGHT, None);
+    animation.add_layer("grid", -1).unwrap();
+    animation.add_layer("glow", 1).unwrap();
+    animation.add_layer("labels", 2).unwrap();
+    if let Some(glow) = animation.layer_mut("glow") {
+        glow.set_blend_mode(BlendMode::Additive);
+    }
+
+    loop {
+        animation.set_camera();
+        let time = animation.time();
+
+        // The whole grid fades in and out together
+        if let Some(grid) = animation.layer_mut("grid") {
+            grid.set_opacity(0.5 + 0.5 * f32::sin(time));
+        }
+
+        // Drawn first but still ends up on top of everything
+        animation.begin_layer("labels").unwrap();
+        draw_text_centered("Layers", 0., 250., 40, WHITE, None);
+        animation.end_layer();
+
+        animation.begin_layer("grid").unwrap();
+        for x in (-600..=600).step_by(50) {
+            draw_line(x as f32, -360., x as f32, 360., 1., GRAY);
+        }
+        for y in (-350..=350).step_by(50) {
+            draw_line(-640., y as f32, 640., y as f32, 1., GRAY);
+        }
+        animation.end_layer();
+
+        let position = vec2(300. * f32::cos(time), 150. * f32::sin(2. * time));
+        draw_circle(position.x, position.y, 40., ORANGE);
+
+        animation.begin_layer("glow").unwrap();
+        for radius in [80., 60., 45.] {
+            draw_circle(
+                position.x,
+                position.y,
+                radius,
+                Color::new(1., 0.5, 0.1, 0.15),
+            );
+        }
+        animation.end_layer();
+
+        animation.set_default_camera();
+        animation.draw_frame();
+
+        next_frame().await;
+    }
+}
@@ -0,0 +1,64 @@
+use macroquad::prelude::*;
+use mqanim::{
+    morph::morph_to,
+    path::Path,
+    sequence::Property,
+    shapes::{Circle, Polygon, Rectangle, Shape},
+    Animation,
+};
+
+const WINDOW_WIDTH: f32 = 1280.0;
+const WINDOW_HEIGHT: f32 = 720.0;
+fn window_conf() -> Conf {
+    Conf {
+        window_title: "Morph Example".to_owned(),
+        sample_count: 16,
+        window_width: WINDOW_WIDTH as i32,
+        window_height: WINDOW_HEIGHT as i32,
+        ..Default::default()
+    }
+}
+
+#[macroquad::main(window_conf)]
+async fn main() {
+    let mut animation = Animation::new(WINDOW_WIDTH, WINDOW_HEIGHT, None);
+
+    let square = Path::from_shape(
+        &Rectangle::new(vec2(0., 0.), vec2(300., 300.))
+            .fill(SKYBLUE)
+            .stroke(WHITE, 4.),
+    );
+    let circle = Path::from_shape(&Circle::new(vec2(0., 0.), 180.).fill(ORANGE));
+    let star = Path::from_shape(
+        &Polygon::new(
+            (0..10)
+                .map(|index| {
+                    let radius = if index % 2 == 0 { 220. } else { 90. };
+                    let angle =
+                        std::f32::consts::FRAC_PI_2 + index as f32 / 10. * std::f32::consts::TAU;
+                    Vec2::from_angle(angle) * radius
+                })
+                .collect(),
+        )
+        .fill(GOLD)
+        .stroke(RED, 3.),
+    );
+    // Glyphs with holes and several outlines, centred roughly on the origin
+    let text = Path::text("mq8", 320., None)
+        .expect("The builtin font has outlines")
+        .position(vec2(-290., -110.))
+        .fill(PURPLE);
+
+    let shape = Property::new(square.clone());
+    {
+        let shape = shape.clone();
+        animation.on_draw(move |_| shape.borrow().draw());
+    }
+
+    loop {
+        for target in [&circle, &star, &text, &square] {
+            animation.wait(0.5).await;
+            animation.play(morph_to(&shape, target.clone()), 1.5).await;
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use macroquad::prelude::*;
+use mqanim::{
+    map,
+    path::Path,
+    plot::Graph,
+    shapes::{Circle, Shape},
+    Animation,
+};
+use std::f32::consts::PI;
+
+const WINDOW_WIDTH: f32 = 1280.0;
+const WINDOW_HEIGHT: f32 = 720.0;
+fn window_conf() -> Conf {
+    Conf {
+        window_title: "Paths Example".to_owned(),
+        sample_count: 16,
+        window_width: WINDOW_WIDTH as i32,
+        window_height: WINDOW_HEIGHT as i32,
+        ..Default::default()
+    }
+}
+
+#[macroquad::main(window_conf)]
+async fn main() {
+    let mut animation = Animation::new(WINDOW_WIDTH, WINDOW_HEIGHT, None);
+
+    let graph = Graph::new(vec2(-300., 0.), vec2(560., 400.), -PI..PI, -1.5..1.5);
+    let sine: Vec<Vec2> = (0..=200)
+        .map(|val| {
+            let x = map(val as f32, 0., 200., -PI, PI);
+            vec2(x, f32::sin(2. * x))
+        })
+        .collect();
+    let sine_path = graph.line_path_vec(&sine).stroke(ORANGE, 4.);
+
+    let curve = Path::new()
+        .move_to(vec2(50., -200.))
+        .cubic_to(vec2(150., 250.), vec2(350., -250.), vec2(450., 200.))
+        .line_to(vec2(550., 200.))
+        .arc(vec2(550., 120.), 80., 90., -180.)
+        .stroke(SKYBLUE, 6.);
+    let square = Path::new()
+        .move_to(vec2(200., 250.))
+        .line_to(vec2(300., 250.))
+        .line_to(vec2(300., 320.))
+        .line_to(vec2(200., 320.))
+        .close()
+        .fill(DARKBLUE)
+        .stroke(WHITE, 3.);
+    let dot = Circle::new(vec2(0., 0.), 10.).fill(RED);
+
+    loop {
+        animation.set_camera();
+        // Draws on over two seconds, holds and starts again
+        let progress = (animation.time() % 3. / 2.).min(1.);
+
+        graph.draw_axes();
+        sine_path.draw_partial(0., progress);
+        curve.draw_partial(0., progress);
+        square.draw_partial(0., progress);
+        // Follows the tip of the curve at a constant speed
+        if let Some(tip) = curve.point_at(progress) {
+            dot.clone().position(tip).draw();
+        }
+
+        animation.set_default_camera();
+        animation.draw_frame();
+
+        next_frame().await;
+    }
+}
@@ -2,6 +2,7 @@ use macroquad::prelude::*;
 use mqanim::{
     map,
     plot::{AxisStyle, Graph, GraphEndPointStyle, GraphStyle, LabelStyle, MarkerStyle, TickStyle},
+    tessellate::{DashPattern, LineCap, LineJoin},
     Animation,
 };
 use std::f32::consts::PI;
@@ -21,7 +22,8 @@ fn window_conf() -> Conf {
 #[macroquad::main(window_conf)]
 async fn main() {
     let mut animation = Animation::new(WINDOW_WIDTH, WINDOW_HEIGHT, None);
-    // animation.enable_fxaa();
+    // animation.enable_fxaa().unwrap();
+    // animation.enable_ssaa(4, mqanim::ssaa::DownsampleFilter::Lanczos).unwrap();
 
     let x_sine: Vec<f32> = (-100..100)
         .map(|val| map(val as f32, -100.0, 100.0, -2. * PI, 2. * PI))
@@ -33,16 +35,15 @@ async fn main() {
         })
         .collect();
 
-    let mut time = 0.;
     loop {
-        time += 0.01;
+        animation.set_camera();
+        let time = animation.time();
         let sine: Vec<Vec2> = (-500..500)
             .map(|val| {
                 let t = map(val as f32, -100.0, 100.0, -2. * PI, 2. * PI);
                 vec2(t, -f32::sin(t + time))
             })
             .collect();
-        animation.set_camera();
         let _mouse = animation.get_world_mouse();
 
         let graph = Graph::new(
@@ -60,6 +61,7 @@ async fn main() {
                         color: WHITE,
                         font_size: 12,
                         decimal_places: 2,
+                        font: None,
                     },
                     marker_style: MarkerStyle {
                         length: 5.,
@@ -70,6 +72,7 @@ async fn main() {
                 end_point_style: GraphEndPointStyle::Arrow { thickness: 7. },
                 line_thickness: 3.,
                 line_color: WHITE,
+                line_dash: None,
             },
             y_style: AxisStyle {
                 tick_step: 0.5,
@@ -79,6 +82,7 @@ async fn main() {
                         color: WHITE,
                         font_size: 12,
                         decimal_places: 2,
+                        font: None,
                     },
                     marker_style: MarkerStyle {
                         length: 5.,
@@ -89,17 +93,30 @@ async fn main() {
                 end_point_style: GraphEndPointStyle::Arrow { thickness: 7. },
                 line_thickness: 3.,
                 line_color: WHITE,
+                line_dash: None,
             },
+            plot_join: LineJoin::Round,
+            plot_cap: LineCap::Round,
         });
         graph.draw_axes();
 
         graph.plot_line_vec(&sine, 3., PURPLE);
-        graph.plot_line_xy(&x_sine, &y_sine, 3., BLUE);
+        graph.plot_line_xy_dashed(&x_sine, &y_sine, 3., BLUE, &DashPattern::dash_dot(20., 8.));
+        // An asymptote with marching dashes
+        graph.plot_line_vec_dashed(
+            &[vec2(-PI / 2., -3.5), vec2(-PI / 2., 3.5)],
+            2.,
+            GRAY,
+            &DashPattern::dashed(15., 10.).offset(time * 20.),
+        );
+        // Translucent lines keep an even colour through the joints
+        graph.plot_line_vec(&sine, 20., Color::new(1., 0.5, 0., 0.3));
 
         let pt = graph.graph_to_world(vec2(-0.5, -0.5));
         draw_circle(pt.x, pt.y, 10., ORANGE);
         graph.plot_pt_vec(&vec2(0.2, 0.2), 10., RED);
         graph.plot_pt_xy(0.4, 0.4, 15., BLUE);
+        graph.plot_guides(&vec2(0.4, 0.4), 3., LIGHTGRAY, &DashPattern::dotted(10.));
 
         animation.set_default_camera();
         animation.draw_frame();
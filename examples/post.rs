@@ -0,0 +1,115 @@
+use macroquad::prelude::*;
+use mqanim::{map, plot::Graph, post::PostEffect, Animation};
+use std::f32::consts::PI;
+
+const WINDOW_WIDTH: f32 = 1280.0;
+const WINDOW_HEIGHT: f32 = 720.0;
+fn window_conf() -> Conf {
+    Conf {
+        window_title: "Post Processing Example".to_owned(),
+        sample_count: 16,
+        window_width: WINDOW_WIDTH as i32,
+        window_height: WINDOW_HEIGHT as i32,
+        ..Default::default()
+    }
+}
+
+const SCANLINES: &str = r#"#version 100
+precision mediump float;
+
+varying vec2 uv;
+
+uniform sampler2D Texture;
+uniform vec2 resolution;
+uniform float time;
+uniform float line_strength;
+
+void main() {
+    vec4 color = texture2D(Texture, uv);
+    float line = 0.5 + 0.5 * sin(uv.y * resolution.y * 1.5 + time * 5.0);
+    gl_FragColor = vec4(color.rgb * (1.0 - line_strength * line), color.a);
+}"#;
+
+// Toggle the passes with the number keys 1 to 7
+#[macroquad::main(window_conf)]
+async fn main() {
+    let mut animation = Animation::new(WINDOW_WIDTH, WINDOW_HEIGHT, None);
+    animation.add_post_pass("fxaa", PostEffect::Fxaa).unwrap();
+    animation
+        .add_post_pass("bloom", PostEffect::bloom())
+        .unwrap();
+    animation
+        .add_post_pass("grade", PostEffect::color_grade())
+        .unwrap();
+    animation
+        .add_post_pass("aberration", PostEffect::chromatic_aberration())
+        .unwrap();
+    animation
+        .add_post_pass("vignette", PostEffect::vignette())
+        .unwrap();
+    animation
+        .add_post_pass("grain", PostEffect::film_grain())
+        .unwrap();
+    // A custom shader can fail to compile, the animation still runs without it
+    if let Err(err) = animation.add_post_pass(
+        "scanlines",
+        PostEffect::Custom {
+            fragment: SCANLINES.to_owned(),
+            uniforms: vec![("line_strength".to_owned(), UniformType::Float1)],
+        },
+    ) {
+        eprintln!("{err}");
+    }
+    if let Some(scanlines) = animation.post_pass("scanlines") {
+        scanlines.set_uniform("line_strength", 0.15f32);
+    }
+
+    let pass_keys = [
+        (KeyCode::Key1, "fxaa"),
+        (KeyCode::Key2, "bloom"),
+        (KeyCode::Key3, "grade"),
+        (KeyCode::Key4, "aberration"),
+        (KeyCode::Key5, "vignette"),
+        (KeyCode::Key6, "grain"),
+        (KeyCode::Key7, "scanlines"),
+    ];
+
+    loop {
+        for (key, name) in pass_keys {
+            if is_key_pressed(key) {
+                if let Some(pass) = animation.post_pass_mut(name) {
+                    pass.set_enabled(!pass.is_enabled());
+                }
+            }
+        }
+
+        animation.set_camera();
+        let time = animation.time();
+        if let Some(PostEffect::ColorGrade { saturation, .. }) = animation
+            .post_pass_mut("grade")
+            .map(|pass| pass.effect_mut())
+        {
+            *saturation = 1. + 0.5 * f32::sin(time);
+        }
+
+        let graph = Graph::new(
+            vec2(0., 0.),
+            vec2(WINDOW_WIDTH - 100., WINDOW_HEIGHT - 100.),
+            -3.5..3.5,
+            -3.5..3.5,
+        );
+        let sine: Vec<Vec2> = (-200..200)
+            .map(|val| {
+                let t = map(val as f32, -200.0, 200.0, -PI, PI);
+                vec2(t, f32::sin(t * 2. + time))
+            })
+            .collect();
+        graph.draw_axes();
+        graph.plot_line_vec(&sine, 4., YELLOW);
+
+        animation.set_default_camera();
+        animation.draw_frame();
+
+        next_frame().await;
+    }
+}
@@ -0,0 +1,115 @@
+use macroquad::prelude::*;
+use mqanim::{
+    easing::Easing,
+    error::Error,
+    scene::{window_conf, Scene, SceneArgs, SceneRunner, Transition},
+    tween::Tween,
+    ui::draw_text_centered,
+    Animation,
+};
+
+const WINDOW_WIDTH: f32 = 1280.0;
+const WINDOW_HEIGHT: f32 = 720.0;
+fn conf() -> Conf {
+    window_conf("Scenes Example", WINDOW_WIDTH, WINDOW_HEIGHT)
+}
+
+struct Title {
+    time: f32,
+}
+
+impl Scene for Title {
+    fn name(&self) -> &str {
+        "title"
+    }
+
+    fn setup(&mut self, _animation: &mut Animation) {
+        self.time = 0.;
+    }
+
+    fn update(&mut self, _animation: &Animation, dt: f32) {
+        self.time += dt;
+    }
+
+    fn draw(&self, _animation: &Animation) {
+        draw_text_centered("Roughly Understood", 0., 0., 40, WHITE, None);
+    }
+
+    fn duration(&self) -> Option<f32> {
+        Some(2.)
+    }
+}
+
+struct Orbit {
+    radius: Tween<f32>,
+    time: f32,
+}
+
+impl Scene for Orbit {
+    fn name(&self) -> &str {
+        "orbit"
+    }
+
+    fn setup(&mut self, _animation: &mut Animation) {
+        self.time = 0.;
+    }
+
+    fn update(&mut self, _animation: &Animation, dt: f32) {
+        self.time += dt;
+    }
+
+    fn draw(&self, _animation: &Animation) {
+        let radius = self.radius.value_at(self.time);
+        draw_circle_lines(0., 0., radius, 2., GRAY);
+        draw_circle(
+            radius * f32::cos(self.time * 2.),
+            radius * f32::sin(self.time * 2.),
+            15.,
+            ORANGE,
+        );
+    }
+
+    fn finished(&self) -> bool {
+        self.radius.is_finished(self.time)
+    }
+}
+
+// Run with `cargo run --example scenes -- --scene orbit` to preview a single scene or
+// `--gif scenes.gif` to export them
+#[macroquad::main(conf)]
+async fn main() {
+    let args = match SceneArgs::parse() {
+        Ok(args) => args,
+        Err(Error::Help) => {
+            println!("{}", Error::Help);
+            return;
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            std::process::exit(2);
+        }
+    };
+    let list = args.list;
+
+    let runner = SceneRunner::new()
+        .scene(Title { time: 0. })
+        .scene(Orbit {
+            radius: Tween::new(50., 300., 4.).easing(Easing::CubicInOut),
+            time: 0.,
+        })
+        .transition(Transition::Fade { duration: 0.5 })
+        .args(args);
+    if list {
+        for name in runner.names() {
+            println!("{name}");
+        }
+        return;
+    }
+
+    let mut animation = Animation::new(WINDOW_WIDTH, WINDOW_HEIGHT, None);
+    let result = runner.run(&mut animation).await;
+    if let Err(err) = result {
+        eprintln!("{err}");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,70 @@
+use macroquad::prelude::*;
+use mqanim::{
+    easing::Easing,
+    sequence::{fade_in, fade_out, lagged_start, parallel, sequence, tween_to, Action, Property},
+    Animation,
+};
+
+const WINDOW_WIDTH: f32 = 1280.0;
+const WINDOW_HEIGHT: f32 = 720.0;
+fn window_conf() -> Conf {
+    Conf {
+        window_title: "Sequence Example".to_owned(),
+        sample_count: 16,
+        window_width: WINDOW_WIDTH as i32,
+        window_height: WINDOW_HEIGHT as i32,
+        ..Default::default()
+    }
+}
+
+#[macroquad::main(window_conf)]
+async fn main() {
+    let mut animation = Animation::new(WINDOW_WIDTH, WINDOW_HEIGHT, None);
+
+    let circle_color = Property::new(Color { a: 0., ..ORANGE });
+    let circle_pos = Property::new(vec2(-300., 0.));
+    let dots: Vec<Property<Color>> = (0..5)
+        .map(|_| Property::new(Color { a: 0., ..SKYBLUE }))
+        .collect();
+
+    {
+        let circle_color = circle_color.clone();
+        let circle_pos = circle_pos.clone();
+        let dots = dots.clone();
+        animation.on_draw(move |_animation| {
+            let pos = circle_pos.get();
+            draw_circle(pos.x, pos.y, 50., circle_color.get());
+
+            for (i, dot) in dots.iter().enumerate() {
+                draw_circle(-200. + i as f32 * 100., -200., 20., dot.get());
+            }
+        });
+    }
+
+    loop {
+        animation.play(fade_in(&circle_color), 1.0).await;
+        animation.wait(0.5).await;
+        animation
+            .play(
+                tween_to(&circle_pos, vec2(300., 0.)).easing(Easing::BackInOut),
+                1.5,
+            )
+            .await;
+        animation
+            .play(
+                lagged_start(dots.iter().map(|dot| fade_in(dot).boxed()).collect(), 0.3),
+                1.5,
+            )
+            .await;
+        animation
+            .play(
+                parallel(vec![
+                    tween_to(&circle_pos, vec2(-300., 0.)).boxed(),
+                    sequence(dots.iter().rev().map(|dot| fade_out(dot).boxed()).collect()).boxed(),
+                ]),
+                2.0,
+            )
+            .await;
+        animation.play(fade_out(&circle_color), 1.0).await;
+    }
+}
@@ -0,0 +1,94 @@
+use macroquad::prelude::*;
+use mqanim::{
+    easing::Easing,
+    sequence::{fade_in, parallel, tween_to, Action, Property},
+    shapes::{Arrow, Circle, Line, Polygon, Rectangle, Shape},
+    tessellate::DashPattern,
+    Animation,
+};
+
+const WINDOW_WIDTH: f32 = 1280.0;
+const WINDOW_HEIGHT: f32 = 720.0;
+fn window_conf() -> Conf {
+    Conf {
+        window_title: "Shapes Example".to_owned(),
+        sample_count: 16,
+        window_width: WINDOW_WIDTH as i32,
+        window_height: WINDOW_HEIGHT as i32,
+        ..Default::default()
+    }
+}
+
+#[macroquad::main(window_conf)]
+async fn main() {
+    let mut animation = Animation::new(WINDOW_WIDTH, WINDOW_HEIGHT, None);
+
+    let circle = Property::new(Circle::new(vec2(-400., 0.), 80.).fill(ORANGE));
+    let square = Property::new(
+        Rectangle::new(vec2(-100., 0.), vec2(150., 150.))
+            .fill(SKYBLUE)
+            .stroke(WHITE, 4.),
+    );
+    let star = Property::new(
+        Polygon::new(
+            (0..10)
+                .map(|index| {
+                    let radius = if index % 2 == 0 { 90. } else { 40. };
+                    let angle =
+                        std::f32::consts::FRAC_PI_2 + index as f32 / 10. * std::f32::consts::TAU;
+                    Vec2::from_angle(angle) * radius
+                })
+                .collect(),
+        )
+        .position(vec2(200., 0.))
+        .fill(GOLD),
+    );
+    let hexagon = Polygon::regular(vec2(450., 0.), 70., 6)
+        .no_fill()
+        .stroke(LIME, 3.);
+    let line = Line::new(vec2(-600., -200.), vec2(600., -200.))
+        .stroke(GRAY, 2.)
+        .dash(DashPattern::dashed(20., 10.));
+    let arrow = Property::new(Arrow::new(vec2(-400., 200.), vec2(-200., 200.)).stroke(RED, 4.));
+
+    {
+        let circle = circle.clone();
+        let square = square.clone();
+        let star = star.clone();
+        let arrow = arrow.clone();
+        animation.on_draw(move |_| {
+            line.draw();
+            circle.borrow().draw();
+            square.borrow().draw();
+            star.borrow().draw();
+            hexagon.draw();
+            arrow.borrow().draw();
+        });
+    }
+
+    animation.play(fade_in(&star), 1.).await;
+
+    let circle_to = Circle::new(vec2(-400., 0.), 120.)
+        .fill(PURPLE)
+        .stroke(WHITE, 6.);
+    let square_to = square.get().rotation(45.).scale(vec2(1.2, 0.6));
+    let arrow_to = Arrow::new(vec2(-400., 200.), vec2(400., 250.)).stroke(RED, 4.);
+    animation
+        .play(
+            parallel(vec![
+                tween_to(&circle, circle_to)
+                    .easing(Easing::SmoothStep)
+                    .boxed(),
+                tween_to(&square, square_to)
+                    .easing(Easing::SmoothStep)
+                    .boxed(),
+                tween_to(&arrow, arrow_to).boxed(),
+            ]),
+            2.,
+        )
+        .await;
+
+    loop {
+        animation.wait(1.).await;
+    }
+}
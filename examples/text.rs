@@ -16,11 +16,11 @@ fn window_conf() -> Conf {
 #[macroquad::main(window_conf)]
 async fn main() {
     let mut animation = Animation::new(WINDOW_WIDTH, WINDOW_HEIGHT, None);
-    // animation.enable_fxaa();
+    // animation.enable_fxaa().unwrap();
 
     loop {
         animation.set_camera();
-        draw_text_centered("Hello World From Droid Sans Mono", 0., 0., 20, WHITE);
+        draw_text_centered("Hello World From Droid Sans Mono", 0., 0., 20, WHITE, None);
 
         animation.set_default_camera();
         animation.draw_frame();
@@ -0,0 +1,66 @@
+use macroquad::prelude::*;
+use mqanim::{
+    easing::Easing,
+    tween::{Repeat, Tween},
+    Animation,
+};
+
+const WINDOW_WIDTH: f32 = 1280.0;
+const WINDOW_HEIGHT: f32 = 720.0;
+fn window_conf() -> Conf {
+    Conf {
+        window_title: "Tween Example".to_owned(),
+        sample_count: 16,
+        window_width: WINDOW_WIDTH as i32,
+        window_height: WINDOW_HEIGHT as i32,
+        ..Default::default()
+    }
+}
+
+#[macroquad::main(window_conf)]
+async fn main() {
+    let mut animation = Animation::new(WINDOW_WIDTH, WINDOW_HEIGHT, None);
+
+    let easings = [
+        Easing::Linear,
+        Easing::QuadInOut,
+        Easing::CubicInOut,
+        Easing::SineInOut,
+        Easing::ExpoInOut,
+        Easing::BackInOut,
+        Easing::ElasticOut,
+        Easing::BounceOut,
+        Easing::CubicBezier(0.68, -0.6, 0.32, 1.6),
+    ];
+    let tweens: Vec<Tween<Vec2>> = easings
+        .iter()
+        .enumerate()
+        .map(|(i, easing)| {
+            let y = 250. - i as f32 * 60.;
+            Tween::new(vec2(-500., y), vec2(500., y), 2.)
+                .delay(0.5)
+                .easing(*easing)
+                .repeat(Repeat::Forever)
+                .yoyo(true)
+        })
+        .collect();
+    let color = Tween::new(ORANGE, SKYBLUE, 2.)
+        .delay(0.5)
+        .repeat(Repeat::Forever)
+        .yoyo(true);
+
+    loop {
+        animation.set_camera();
+        let time = animation.time();
+
+        for tween in &tweens {
+            let pos = tween.value_at(time);
+            draw_circle(pos.x, pos.y, 15., color.value_at(time));
+        }
+
+        animation.set_default_camera();
+        animation.draw_frame();
+
+        next_frame().await;
+    }
+}
@@ -1,5 +1,8 @@
 use macroquad::prelude::*;
-use mqanim::Animation;
+use mqanim::{
+    scaling::{Letterbox, ScaleMode},
+    Animation,
+};
 
 const WINDOW_WIDTH: f32 = 1280.0;
 const WINDOW_HEIGHT: f32 = 720.0;
@@ -16,11 +19,28 @@ fn window_conf() -> Conf {
 #[macroquad::main(window_conf)]
 async fn main() {
     let mut animation = Animation::new(WINDOW_WIDTH, WINDOW_HEIGHT, None);
-    // animation.enable_fxaa();
+    // animation.enable_fxaa().unwrap();
+
+    animation.set_letterbox(Letterbox::Color(BLACK));
+
+    // Tab cycles through the scale modes, the buttons keep following the mouse in each
+    let scale_modes = [
+        ScaleMode::Fit,
+        ScaleMode::Fill,
+        ScaleMode::Stretch,
+        ScaleMode::Integer,
+        ScaleMode::Fixed,
+    ];
+    let mut scale_mode = 0;
 
     let mut button_pushed = false;
     let mut circle_button_pushed = false;
     loop {
+        if is_key_pressed(KeyCode::Tab) {
+            scale_mode = (scale_mode + 1) % scale_modes.len();
+            animation.set_scale_mode(scale_modes[scale_mode]);
+        }
+
         animation.set_camera();
         mqanim::ui::Button::new(
             vec2(0., 0.),
@@ -29,6 +49,7 @@ async fn main() {
                 height: 75.,
             },
         )
+        .label(mqanim::ui::ButtonLabel::new("Toggle"))
         .mouse_pos(animation.get_world_mouse())
         .draw(&mut button_pushed);
 
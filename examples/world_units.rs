@@ -0,0 +1,47 @@
+use macroquad::prelude::*;
+use mqanim::{
+    shapes::{Arrow, Shape},
+    ui::draw_text_centered,
+    Animation,
+};
+
+const WINDOW_WIDTH: f32 = 1280.0;
+const WINDOW_HEIGHT: f32 = 720.0;
+fn window_conf() -> Conf {
+    Conf {
+        window_title: "World Units Example".to_owned(),
+        sample_count: 16,
+        window_width: WINDOW_WIDTH as i32,
+        window_height: WINDOW_HEIGHT as i32,
+        ..Default::default()
+    }
+}
+
+#[macroquad::main(window_conf)]
+async fn main() {
+    let mut animation = Animation::new(WINDOW_WIDTH, WINDOW_HEIGHT, None);
+    // x goes from -8 to 8 and y from -4.5 to 4.5 whatever the window or render resolution
+    animation
+        .set_world_width(16.)
+        .expect("Failed to set the world size");
+
+    let line_thickness = animation.pixels_to_units(3.);
+    let font_size = animation.units_to_pixels(0.5) as u16;
+    // The head and stroke are sized in pixels, the arrow itself in units
+    let arrow = Arrow::for_animation(vec2(-6., -3.), vec2(-2., -3.), &animation);
+
+    loop {
+        animation.set_camera();
+        for x in -8..=8 {
+            draw_line(x as f32, -4.5, x as f32, 4.5, line_thickness, DARKGRAY);
+        }
+        draw_circle(2. * animation.time().cos(), 0., 1., ORANGE);
+        arrow.draw();
+        draw_text_centered("Half a unit tall", 0., 3., font_size, WHITE, None);
+
+        animation.set_default_camera();
+        animation.draw_frame();
+
+        next_frame().await;
+    }
+}
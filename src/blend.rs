@@ -0,0 +1,153 @@
+use macroquad::{
+    miniquad::{BlendFactor, BlendState, BlendValue, Equation},
+    prelude::*,
+};
+
+use crate::error::Error;
+
+// Everything is drawn into the render target with premultiplied alpha. Colours still blend with
+// the usual source alpha but the alpha channel accumulates coverage, so a render target cleared to
+// transparent ends up with the real opacity of the scene in its alpha channel.
+pub(crate) fn scene_material() -> Result<Material, Error> {
+    load_blend_material(
+        "scene",
+        BlendState::new(
+            Equation::Add,
+            BlendFactor::Value(BlendValue::SourceAlpha),
+            BlendFactor::OneMinusValue(BlendValue::SourceAlpha),
+        ),
+        over(),
+    )
+}
+
+// Draws a premultiplied texture over what is already there, used to put frames on the screen
+pub(crate) fn premultiplied_material() -> Result<Material, Error> {
+    load_blend_material("normal blending", over(), over())
+}
+
+// Premultiplied colour, or alpha, drawn over what is already there
+fn over() -> BlendState {
+    BlendState::new(
+        Equation::Add,
+        BlendFactor::One,
+        BlendFactor::OneMinusValue(BlendValue::SourceAlpha),
+    )
+}
+
+// How a layer is combined with the layers below it
+#[derive(Default, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum BlendMode {
+    #[default]
+    Normal,
+    // Adds the layer's light, good for glows
+    Additive,
+    // Darkens by multiplying with what is below, white leaves it unchanged
+    Multiply,
+    // Lightens, the inverse of multiplying the inverted colours
+    Screen,
+}
+
+impl BlendMode {
+    pub(crate) const ALL: [BlendMode; 4] = [
+        BlendMode::Normal,
+        BlendMode::Additive,
+        BlendMode::Multiply,
+        BlendMode::Screen,
+    ];
+
+    // The passes drawing a premultiplied source with this blending, in order. The alpha always
+    // builds up like a normal layer.
+    pub(crate) fn materials(&self) -> Result<Vec<Material>, Error> {
+        let color = |source, destination| BlendState::new(Equation::Add, source, destination);
+        match self {
+            BlendMode::Normal => Ok(vec![premultiplied_material()?]),
+            BlendMode::Additive => Ok(vec![load_blend_material(
+                "additive blending",
+                color(BlendFactor::One, BlendFactor::One),
+                over(),
+            )?]),
+            // src * dst + src * (1 - dst alpha) + dst * (1 - src alpha), so the layer shows
+            // unchanged where there is nothing below it. The last term needs the original
+            // destination alpha, which the first pass leaves alone.
+            BlendMode::Multiply => Ok(vec![
+                load_blend_material(
+                    "multiply blending",
+                    color(
+                        BlendFactor::Value(BlendValue::DestinationColor),
+                        BlendFactor::OneMinusValue(BlendValue::SourceAlpha),
+                    ),
+                    BlendState::new(Equation::Add, BlendFactor::Zero, BlendFactor::One),
+                )?,
+                load_blend_material(
+                    "multiply blending",
+                    color(
+                        BlendFactor::OneMinusValue(BlendValue::DestinationAlpha),
+                        BlendFactor::One,
+                    ),
+                    over(),
+                )?,
+            ]),
+            BlendMode::Screen => Ok(vec![load_blend_material(
+                "screen blending",
+                color(
+                    BlendFactor::OneMinusValue(BlendValue::DestinationColor),
+                    BlendFactor::One,
+                ),
+                over(),
+            )?]),
+        }
+    }
+}
+
+fn load_blend_material(
+    name: &str,
+    color_blend: BlendState,
+    alpha_blend: BlendState,
+) -> Result<Material, Error> {
+    load_material(
+        ShaderSource::Glsl {
+            vertex: VERTEX_SHADER,
+            fragment: FRAGMENT_SHADER,
+        },
+        MaterialParams {
+            pipeline_params: PipelineParams {
+                color_blend: Some(color_blend),
+                alpha_blend: Some(alpha_blend),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    )
+    .map_err(|source| Error::Shader {
+        name: name.to_owned(),
+        source,
+    })
+}
+
+// The same shaders macroquad draws with by default
+const VERTEX_SHADER: &str = r#"#version 100
+attribute vec3 position;
+attribute vec2 texcoord;
+attribute vec4 color0;
+
+varying lowp vec2 uv;
+varying lowp vec4 color;
+
+uniform mat4 Model;
+uniform mat4 Projection;
+
+void main() {
+    gl_Position = Projection * Model * vec4(position, 1);
+    color = color0 / 255.0;
+    uv = texcoord;
+}"#;
+
+const FRAGMENT_SHADER: &str = r#"#version 100
+varying lowp vec4 color;
+varying lowp vec2 uv;
+
+uniform sampler2D Texture;
+
+void main() {
+    gl_FragColor = color * texture2D(Texture, uv);
+}"#;
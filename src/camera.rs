@@ -0,0 +1,134 @@
+use macroquad::prelude::*;
+
+use crate::{error::Error, tween::Lerp};
+
+#[derive(Copy, Clone, Debug)]
+pub struct CameraView {
+    pub position: Vec2, // World position shown at the centre of the frame
+    pub zoom: f32,      // 2.0 shows half as much of the world in each direction
+    pub rotation: f32,  // Degrees counter clockwise, the world appears to turn clockwise
+}
+
+impl Default for CameraView {
+    fn default() -> Self {
+        Self {
+            position: vec2(0., 0.),
+            zoom: 1.,
+            rotation: 0.,
+        }
+    }
+}
+
+impl CameraView {
+    pub fn new(position: Vec2, zoom: f32, rotation: f32) -> Self {
+        Self {
+            position,
+            zoom,
+            rotation,
+        }
+    }
+
+    // The unrotated view that fits the whole world rect inside a frame of the given world size.
+    // Fails for rects without an area.
+    pub fn fit(rect: Rect, frame_size: Vec2) -> Result<Self, Error> {
+        if !(rect.w > 0. && rect.h > 0. && rect.size().is_finite()) {
+            return Err(Error::InvalidViewRect {
+                width: rect.w,
+                height: rect.h,
+            });
+        }
+        let view = Self {
+            position: rect.center(),
+            zoom: f32::min(frame_size.x / rect.w, frame_size.y / rect.h),
+            rotation: 0.,
+        };
+        view.validate()?;
+        Ok(view)
+    }
+
+    // A zoom of zero or less can't be inverted to map the screen back into the world
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        if !(self.zoom > 0. && self.zoom.is_finite()) {
+            return Err(Error::InvalidZoom(self.zoom));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn apply(&self, camera: &mut Camera2D, frame_size: Vec2) {
+        camera.target = self.position;
+        camera.zoom = vec2(2. / frame_size.x, -2. / frame_size.y) * self.zoom;
+        camera.rotation = -self.rotation;
+    }
+}
+
+impl Lerp for CameraView {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        // Zoom is interpolated exponentially so zooming in by 2x takes as long as zooming out by 2x.
+        // That needs both zooms positive, anything else is interpolated linearly.
+        let zoom = if self.zoom > 0. && other.zoom > 0. {
+            f32::exp(self.zoom.ln().lerp(&other.zoom.ln(), t))
+        } else {
+            self.zoom.lerp(&other.zoom, t)
+        };
+        Self {
+            position: self.position.lerp(other.position, t),
+            zoom,
+            rotation: self.rotation.lerp(&other.rotation, t),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zoom_is_interpolated_exponentially() {
+        let from = CameraView::new(vec2(0., 0.), 1., 0.);
+        let to = CameraView::new(vec2(10., 0.), 4., 90.);
+        let view = from.lerp(&to, 0.5);
+        assert!((view.zoom - 2.).abs() < 1e-5);
+        assert_eq!(view.position, vec2(5., 0.));
+        assert_eq!(view.rotation, 45.);
+    }
+
+    #[test]
+    fn zoom_without_a_logarithm_is_interpolated_linearly() {
+        let from = CameraView::new(vec2(0., 0.), 0., 0.);
+        let to = CameraView::new(vec2(0., 0.), 2., 0.);
+        assert_eq!(from.lerp(&to, 0.5).zoom, 1.);
+        assert_eq!(to.lerp(&CameraView { zoom: -2., ..to }, 0.25).zoom, 1.);
+    }
+
+    #[test]
+    fn fit_shows_the_whole_rect() {
+        let view = CameraView::fit(Rect::new(0., 0., 4., 1.), vec2(16., 9.)).unwrap();
+        assert_eq!(view.position, vec2(2., 0.5));
+        assert_eq!(view.zoom, 4.);
+    }
+
+    #[test]
+    fn rejects_zooms_that_arent_positive() {
+        for zoom in [0., -2., f32::NAN, f32::INFINITY] {
+            assert!(matches!(
+                CameraView::new(vec2(0., 0.), zoom, 0.).validate(),
+                Err(Error::InvalidZoom(_))
+            ));
+        }
+        assert!(CameraView::default().validate().is_ok());
+    }
+
+    #[test]
+    fn fit_rejects_rects_without_an_area() {
+        for (width, height) in [(0., 1.), (1., 0.), (-4., 2.)] {
+            assert!(matches!(
+                CameraView::fit(Rect::new(0., 0., width, height), vec2(16., 9.)),
+                Err(Error::InvalidViewRect { .. })
+            ));
+        }
+        assert!(matches!(
+            CameraView::fit(Rect::new(0., 0., 4., 1.), vec2(0., 0.)),
+            Err(Error::InvalidZoom(_))
+        ));
+    }
+}
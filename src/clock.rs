@@ -0,0 +1,245 @@
+use macroquad::prelude::*;
+
+use crate::error::Error;
+
+const DEFAULT_TARGET_FPS: f32 = 60.;
+
+pub struct Clock {
+    time: f32,
+    sub_frame_offset: f32,
+    dt: f32,
+    frame: u64,
+    // The time and frame fixed steps are counted from, so long runs of them don't add up rounding
+    // errors. Cleared by anything that changes the step.
+    fixed_origin: Option<(f64, u64)>,
+    target_fps: f32,
+    fixed_step: bool,
+    time_scale: f32,
+    paused: bool,
+    started: bool,
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self {
+            time: 0.,
+            sub_frame_offset: 0.,
+            dt: 0.,
+            frame: 0,
+            fixed_origin: None,
+            target_fps: DEFAULT_TARGET_FPS,
+            fixed_step: false,
+            time_scale: 1.,
+            paused: false,
+            started: false,
+        }
+    }
+}
+
+impl Clock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn time(&self) -> f32 {
+        self.time + self.sub_frame_offset
+    }
+
+    // The amount of animation time the current frame advanced by
+    pub fn dt(&self) -> f32 {
+        self.dt
+    }
+
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    pub fn target_fps(&self) -> f32 {
+        self.target_fps
+    }
+
+    pub fn set_target_fps(&mut self, target_fps: f32) -> Result<(), Error> {
+        if !(target_fps > 0. && target_fps.is_finite()) {
+            return Err(Error::InvalidTargetFps(target_fps));
+        }
+        self.target_fps = target_fps;
+        self.fixed_origin = None;
+        Ok(())
+    }
+
+    pub fn fixed_step(&self) -> bool {
+        self.fixed_step
+    }
+
+    // When enabled every frame advances by exactly 1 / target_fps regardless of the real frame time
+    pub fn set_fixed_step(&mut self, fixed_step: bool) {
+        self.fixed_step = fixed_step;
+    }
+
+    pub fn time_scale(&self) -> f32 {
+        self.time_scale
+    }
+
+    // 0.5 plays at half speed, 2.0 at double speed and 0 stops the time
+    pub fn set_time_scale(&mut self, time_scale: f32) -> Result<(), Error> {
+        if !(time_scale >= 0. && time_scale.is_finite()) {
+            return Err(Error::InvalidTimeScale(time_scale));
+        }
+        self.time_scale = time_scale;
+        self.fixed_origin = None;
+        Ok(())
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    // Jumps to the given time, the next frame is rendered at exactly this time. Anything driven
+    // by the animation time follows, state accumulated from dt does not rewind.
+    pub fn seek(&mut self, time: f32) {
+        self.time = time.max(0.);
+        self.sub_frame_offset = 0.;
+        self.frame = (self.time * self.target_fps).round() as u64;
+        self.fixed_origin = None;
+        self.started = false;
+    }
+
+    // Moves the given number of fixed steps forwards, or backwards when negative
+    pub fn step_frames(&mut self, frames: i64) {
+        self.seek(self.time + frames as f32 / self.target_fps);
+    }
+
+    pub fn restart(&mut self) {
+        self.seek(0.);
+    }
+
+    // Moves the time to the given fraction of a fixed step past the frame, for rendering motion
+    // blur sub frames. The first sub frame of a frame ticks the clock. dt is the time since the
+    // previous sub frame, so state driven by dt moves through the same moments as the time.
+    pub(crate) fn tick_sub_frame(&mut self, fraction: f32, new_frame: bool) {
+        let previous = self.time();
+        if new_frame {
+            self.tick(true);
+        }
+        if !self.paused {
+            self.sub_frame_offset = fraction * self.time_scale / self.target_fps;
+        }
+        self.dt = self.time() - previous;
+    }
+
+    // Advances the clock by one frame. The first tick starts the clock at time zero so
+    // the first rendered frame is always frame 0 at t = 0, the same goes for the tick after a seek.
+    pub(crate) fn tick(&mut self, force_fixed_step: bool) {
+        self.sub_frame_offset = 0.;
+        if !self.started {
+            self.started = true;
+            self.dt = 0.;
+            return;
+        }
+
+        if self.paused {
+            self.dt = 0.;
+            return;
+        }
+
+        self.frame += 1;
+        let previous = self.time;
+        if self.fixed_step || force_fixed_step {
+            let (origin_time, origin_frame) = *self
+                .fixed_origin
+                .get_or_insert((self.time as f64, self.frame - 1));
+            let steps = (self.frame - origin_frame) as f64;
+            self.time =
+                (origin_time + steps * self.time_scale as f64 / self.target_fps as f64) as f32;
+        } else {
+            self.fixed_origin = None;
+            self.time += get_frame_time() * self.time_scale;
+        }
+        self.dt = self.time - previous;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_target_fps_that_isnt_positive() {
+        let mut clock = Clock::new();
+        for fps in [0., -30., f32::NAN, f32::INFINITY] {
+            assert!(matches!(
+                clock.set_target_fps(fps),
+                Err(Error::InvalidTargetFps(_))
+            ));
+        }
+        assert_eq!(clock.target_fps(), DEFAULT_TARGET_FPS);
+    }
+
+    #[test]
+    fn rejects_negative_time_scales() {
+        let mut clock = Clock::new();
+        for time_scale in [-1., f32::NAN, f32::INFINITY] {
+            assert!(matches!(
+                clock.set_time_scale(time_scale),
+                Err(Error::InvalidTimeScale(_))
+            ));
+        }
+        assert_eq!(clock.time_scale(), 1.);
+        assert!(clock.set_time_scale(0.).is_ok());
+    }
+
+    #[test]
+    fn sub_frames_split_dt() {
+        let mut clock = Clock::new();
+        clock.set_target_fps(10.).unwrap();
+        let mut times = Vec::new();
+        let mut total = 0.;
+        for frame in 0..3 {
+            for sub_frame in 0..4 {
+                clock.tick_sub_frame(sub_frame as f32 / 8., sub_frame == 0);
+                times.push(clock.time());
+                if frame > 0 {
+                    total += clock.dt();
+                }
+            }
+        }
+        // Half the step is spent between the sub frames of a frame, the other half before the next
+        let expected = [0., 0.0125, 0.025, 0.0375, 0.1, 0.1125];
+        for (time, expected) in times.iter().zip(expected) {
+            assert!((time - expected).abs() < 1e-6);
+        }
+        assert!((total - (times[11] - times[3])).abs() < 1e-6);
+    }
+
+    #[test]
+    fn long_fixed_step_runs_dont_drift() {
+        let mut clock = Clock::new();
+        clock.set_target_fps(60.).unwrap();
+        for _ in 0..=100_000 {
+            clock.tick(true);
+        }
+        assert_eq!(clock.frame(), 100_000);
+        assert_eq!(clock.time(), (100_000. / 60f64) as f32);
+    }
+
+    #[test]
+    fn fixed_steps_follow_the_target_fps() {
+        let mut clock = Clock::new();
+        clock.set_target_fps(25.).unwrap();
+        clock.set_time_scale(2.).unwrap();
+        // The first tick starts the clock without advancing it
+        for _ in 0..11 {
+            clock.tick(true);
+        }
+        assert_eq!(clock.frame(), 10);
+        assert!((clock.time() - 0.8).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,326 @@
+use std::{f32::consts::PI, str::FromStr};
+
+const BACK_OVERSHOOT: f32 = 1.70158;
+const BEZIER_NEWTON_ITERATIONS: usize = 8;
+const BEZIER_BISECTION_ITERATIONS: usize = 20;
+const BEZIER_EPSILON: f32 = 1e-6;
+
+// All easing functions map t in 0 -> 1 to a progress where ease(0) = 0 and ease(1) = 1.
+// Back and elastic easings overshoot outside of 0 -> 1 in between.
+#[derive(Default, Copy, Clone)]
+pub enum Easing {
+    #[default]
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    QuintIn,
+    QuintOut,
+    QuintInOut,
+    SineIn,
+    SineOut,
+    SineInOut,
+    ExpoIn,
+    ExpoOut,
+    ExpoInOut,
+    BackIn,
+    BackOut,
+    BackInOut,
+    ElasticIn,
+    ElasticOut,
+    ElasticInOut,
+    BounceIn,
+    BounceOut,
+    BounceInOut,
+    SmoothStep,
+    // Control points of a css style cubic bezier from (0, 0) to (1, 1)
+    CubicBezier(f32, f32, f32, f32),
+    Custom(fn(f32) -> f32),
+}
+
+impl Easing {
+    pub fn ease(&self, t: f32) -> f32 {
+        let t = t.clamp(0., 1.);
+        match *self {
+            Easing::Linear => t,
+            Easing::QuadIn => t * t,
+            Easing::QuadOut => out(t, |t| t * t),
+            Easing::QuadInOut => in_out(t, |t| t * t),
+            Easing::CubicIn => t * t * t,
+            Easing::CubicOut => out(t, |t| t * t * t),
+            Easing::CubicInOut => in_out(t, |t| t * t * t),
+            Easing::QuintIn => t.powi(5),
+            Easing::QuintOut => out(t, |t| t.powi(5)),
+            Easing::QuintInOut => in_out(t, |t| t.powi(5)),
+            Easing::SineIn => sine_in(t),
+            Easing::SineOut => out(t, sine_in),
+            Easing::SineInOut => in_out(t, sine_in),
+            Easing::ExpoIn => expo_in(t),
+            Easing::ExpoOut => out(t, expo_in),
+            Easing::ExpoInOut => in_out(t, expo_in),
+            Easing::BackIn => back_in(t),
+            Easing::BackOut => out(t, back_in),
+            Easing::BackInOut => in_out(t, back_in),
+            Easing::ElasticIn => elastic_in(t),
+            Easing::ElasticOut => out(t, elastic_in),
+            Easing::ElasticInOut => in_out(t, elastic_in),
+            Easing::BounceIn => 1. - bounce_out(1. - t),
+            Easing::BounceOut => bounce_out(t),
+            Easing::BounceInOut => in_out(t, |t| 1. - bounce_out(1. - t)),
+            Easing::SmoothStep => t * t * (3. - 2. * t),
+            Easing::CubicBezier(x1, y1, x2, y2) => cubic_bezier(t, x1, y1, x2, y2),
+            Easing::Custom(ease) => ease(t),
+        }
+    }
+}
+
+// Mirrors an ease in function to get the matching ease out
+fn out(t: f32, ease_in: impl Fn(f32) -> f32) -> f32 {
+    1. - ease_in(1. - t)
+}
+
+// Eases in for the first half and out for the second half
+fn in_out(t: f32, ease_in: impl Fn(f32) -> f32) -> f32 {
+    if t < 0.5 {
+        0.5 * ease_in(2. * t)
+    } else {
+        1. - 0.5 * ease_in(2. - 2. * t)
+    }
+}
+
+fn sine_in(t: f32) -> f32 {
+    1. - f32::cos(t * PI / 2.)
+}
+
+fn expo_in(t: f32) -> f32 {
+    if t == 0. {
+        0.
+    } else {
+        f32::powf(2., 10. * t - 10.)
+    }
+}
+
+fn back_in(t: f32) -> f32 {
+    (BACK_OVERSHOOT + 1.) * t * t * t - BACK_OVERSHOOT * t * t
+}
+
+fn elastic_in(t: f32) -> f32 {
+    if t == 0. || t == 1. {
+        return t;
+    }
+    -f32::powf(2., 10. * t - 10.) * f32::sin((t * 10. - 10.75) * (2. * PI / 3.))
+}
+
+fn bounce_out(t: f32) -> f32 {
+    const N: f32 = 7.5625;
+    const D: f32 = 2.75;
+
+    if t < 1. / D {
+        N * t * t
+    } else if t < 2. / D {
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    } else if t < 2.5 / D {
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D;
+        N * t * t + 0.984375
+    }
+}
+
+fn cubic_bezier(x: f32, x1: f32, y1: f32, x2: f32, y2: f32) -> f32 {
+    // One dimensional bezier with end points at 0 and 1
+    let bezier = |t: f32, p1: f32, p2: f32| {
+        let inv = 1. - t;
+        3. * inv * inv * t * p1 + 3. * inv * t * t * p2 + t * t * t
+    };
+    let derivative = |t: f32, p1: f32, p2: f32| {
+        let inv = 1. - t;
+        3. * inv * inv * p1 + 6. * inv * t * (p2 - p1) + 3. * t * t * (1. - p2)
+    };
+
+    // Find the curve parameter with the given x, newton's method first then bisection if it
+    // fails to converge
+    let mut t = x;
+    for _ in 0..BEZIER_NEWTON_ITERATIONS {
+        let error = bezier(t, x1, x2) - x;
+        if error.abs() < BEZIER_EPSILON {
+            return bezier(t, y1, y2);
+        }
+        let slope = derivative(t, x1, x2);
+        if slope.abs() < BEZIER_EPSILON {
+            break;
+        }
+        t -= error / slope;
+    }
+
+    let (mut low, mut high) = (0., 1.);
+    t = x;
+    for _ in 0..BEZIER_BISECTION_ITERATIONS {
+        let value = bezier(t, x1, x2);
+        if (value - x).abs() < BEZIER_EPSILON {
+            break;
+        }
+        if value < x {
+            low = t;
+        } else {
+            high = t;
+        }
+        t = (low + high) / 2.;
+    }
+
+    bezier(t, y1, y2)
+}
+
+impl FromStr for Easing {
+    type Err = String;
+
+    // Parses the snake case name of an easing, e.g. "cubic_in_out" or "cubic_bezier(0.4, 0, 0.2, 1)"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some(args) = s
+            .strip_prefix("cubic_bezier(")
+            .and_then(|rest| rest.strip_suffix(')'))
+        {
+            let points = args
+                .split(',')
+                .map(|arg| arg.trim().parse::<f32>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|err| format!("Invalid cubic_bezier control point: {err}"))?;
+
+            return match points[..] {
+                [x1, y1, x2, y2] => Ok(Easing::CubicBezier(x1, y1, x2, y2)),
+                _ => Err("cubic_bezier needs exactly 4 control points".to_owned()),
+            };
+        }
+
+        let easing = match s {
+            "linear" => Easing::Linear,
+            "quad_in" => Easing::QuadIn,
+            "quad_out" => Easing::QuadOut,
+            "quad_in_out" => Easing::QuadInOut,
+            "cubic_in" => Easing::CubicIn,
+            "cubic_out" => Easing::CubicOut,
+            "cubic_in_out" => Easing::CubicInOut,
+            "quint_in" => Easing::QuintIn,
+            "quint_out" => Easing::QuintOut,
+            "quint_in_out" => Easing::QuintInOut,
+            "sine_in" => Easing::SineIn,
+            "sine_out" => Easing::SineOut,
+            "sine_in_out" => Easing::SineInOut,
+            "expo_in" => Easing::ExpoIn,
+            "expo_out" => Easing::ExpoOut,
+            "expo_in_out" => Easing::ExpoInOut,
+            "back_in" => Easing::BackIn,
+            "back_out" => Easing::BackOut,
+            "back_in_out" => Easing::BackInOut,
+            "elastic_in" => Easing::ElasticIn,
+            "elastic_out" => Easing::ElasticOut,
+            "elastic_in_out" => Easing::ElasticInOut,
+            "bounce_in" => Easing::BounceIn,
+            "bounce_out" => Easing::BounceOut,
+            "bounce_in_out" => Easing::BounceInOut,
+            "smooth_step" => Easing::SmoothStep,
+            _ => return Err(format!("Unknown easing {s}")),
+        };
+
+        Ok(easing)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NAMES: [&str; 26] = [
+        "linear",
+        "quad_in",
+        "quad_out",
+        "quad_in_out",
+        "cubic_in",
+        "cubic_out",
+        "cubic_in_out",
+        "quint_in",
+        "quint_out",
+        "quint_in_out",
+        "sine_in",
+        "sine_out",
+        "sine_in_out",
+        "expo_in",
+        "expo_out",
+        "expo_in_out",
+        "back_in",
+        "back_out",
+        "back_in_out",
+        "elastic_in",
+        "elastic_out",
+        "elastic_in_out",
+        "bounce_in",
+        "bounce_out",
+        "bounce_in_out",
+        "smooth_step",
+    ];
+
+    #[test]
+    fn every_easing_starts_at_0_and_ends_at_1() {
+        for name in NAMES {
+            let easing: Easing = name.parse().unwrap();
+            assert!(easing.ease(0.).abs() < 1e-6, "{name}");
+            assert!((easing.ease(1.) - 1.).abs() < 1e-6, "{name}");
+            // Input outside of 0 -> 1 is clamped
+            assert_eq!(easing.ease(-1.), easing.ease(0.), "{name}");
+            assert_eq!(easing.ease(2.), easing.ease(1.), "{name}");
+        }
+    }
+
+    #[test]
+    fn in_out_easings_are_symmetric() {
+        for name in NAMES.iter().filter(|name| name.ends_with("in_out")) {
+            let easing: Easing = name.parse().unwrap();
+            for t in [0.1, 0.25, 0.4] {
+                let sum = easing.ease(t) + easing.ease(1. - t);
+                assert!((sum - 1.).abs() < 1e-5, "{name} at {t}");
+            }
+        }
+    }
+
+    #[test]
+    fn curves_have_their_shape() {
+        assert_eq!(Easing::QuadIn.ease(0.5), 0.25);
+        assert_eq!(Easing::QuadOut.ease(0.5), 0.75);
+        assert_eq!(Easing::SmoothStep.ease(0.5), 0.5);
+        assert!(Easing::BackIn.ease(0.2) < 0.);
+        assert!(Easing::ElasticOut.ease(0.2) > 1.);
+        assert!((Easing::BounceOut.ease(1. / 2.75) - 1.).abs() < 1e-5);
+    }
+
+    #[test]
+    fn cubic_beziers_follow_their_control_points() {
+        let linear = Easing::CubicBezier(0.25, 0.25, 0.75, 0.75);
+        let ease = Easing::CubicBezier(0.25, 0.1, 0.25, 1.);
+        for t in [0.1, 0.3, 0.5, 0.9] {
+            assert!((linear.ease(t) - t).abs() < 1e-4);
+        }
+        // css ease at the halfway point
+        assert!((ease.ease(0.5) - 0.8024).abs() < 1e-3);
+        // Control points so steep newton's method stalls still solve
+        let steep = Easing::CubicBezier(0., 1., 0., 1.);
+        assert!(steep.ease(0.01) > 0.1);
+    }
+
+    #[test]
+    fn parses_names_and_bezier_control_points() {
+        assert!(matches!(" quad_in ".parse(), Ok(Easing::QuadIn)));
+        assert!(matches!(
+            "cubic_bezier(0.4, 0, 0.2, 1)".parse(),
+            Ok(Easing::CubicBezier(x1, y1, x2, y2)) if (x1, y1, x2, y2) == (0.4, 0., 0.2, 1.)
+        ));
+        assert!("cubic_bezier(0.4, 0, 0.2)".parse::<Easing>().is_err());
+        assert!("cubic_bezier(a, 0, 0.2, 1)".parse::<Easing>().is_err());
+        assert!("quad".parse::<Easing>().is_err());
+    }
+}
@@ -0,0 +1,125 @@
+use std::{fmt, io};
+
+#[derive(Debug)]
+pub enum Error {
+    // A font that failed to load, e.g. a file that isn't a valid TTF or OTF
+    Font {
+        name: String,
+        source: macroquad::Error,
+    },
+    // A shader that failed to compile or link, named after the pass it belongs to
+    Shader {
+        name: String,
+        source: macroquad::Error,
+    },
+    InvalidRange {
+        name: &'static str,
+        start: f32,
+        end: f32,
+    },
+    InvalidSsaaFactor(u32),
+    InvalidZoom(f32),
+    // A rect the camera can't fit because it has no area
+    InvalidViewRect {
+        width: f32,
+        height: f32,
+    },
+    // A world size that isn't positive or doesn't have the aspect ratio of the frame
+    InvalidWorldSize {
+        width: f32,
+        height: f32,
+    },
+    InvalidTargetFps(f32),
+    InvalidTimeScale(f32),
+    InvalidSubFrames(u32),
+    InvalidShutterAngle(f32),
+    UnknownLayer(String),
+    UnknownInset(String),
+    UnknownScene(String),
+    // Command line arguments that can't be read, the message says which
+    Usage(String),
+    // --help was given, displays the usage
+    Help,
+    // A frame drawn while the render target camera is still set
+    RenderState,
+    // A layer or inset begun outside of set_camera
+    CameraNotSet,
+    Export(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Font { name, source } => write!(f, "Failed to load the font {name}: {source}"),
+            Error::Shader { name, source } => {
+                write!(f, "Failed to load the shader for {name}: {source}")
+            }
+            Error::InvalidRange { name, start, end } => write!(
+                f,
+                "The {name} must have start smaller than end, got {start}..{end}"
+            ),
+            Error::InvalidSsaaFactor(factor) => write!(
+                f,
+                "The SSAA factor must be between 2 and {}, got {factor}",
+                crate::ssaa::MAX_SSAA_FACTOR
+            ),
+            Error::InvalidZoom(zoom) => {
+                write!(f, "The camera zoom must be greater than zero, got {zoom}")
+            }
+            Error::InvalidViewRect { width, height } => write!(
+                f,
+                "The camera can only fit rects with a positive size, got {width}x{height}"
+            ),
+            Error::InvalidWorldSize { width, height } => write!(
+                f,
+                "The world size must be positive with the aspect ratio of the frame, got {width}x{height}"
+            ),
+            Error::InvalidTargetFps(fps) => write!(
+                f,
+                "The target fps must be greater than zero, got {fps}"
+            ),
+            Error::InvalidTimeScale(time_scale) => write!(
+                f,
+                "The time scale can't be negative, got {time_scale}"
+            ),
+            Error::InvalidSubFrames(sub_frames) => write!(
+                f,
+                "Motion blur needs at least 1 sub frame, got {sub_frames}"
+            ),
+            Error::InvalidShutterAngle(angle) => write!(
+                f,
+                "The shutter angle must be between 0 and 360 degrees, got {angle}"
+            ),
+            Error::UnknownLayer(name) => write!(f, "There is no layer named {name}"),
+            Error::UnknownInset(name) => write!(f, "There is no inset named {name}"),
+            Error::UnknownScene(name) => write!(f, "There is no scene named {name}"),
+            Error::Usage(message) => write!(f, "{message}\n\n{}", crate::scene::USAGE),
+            Error::Help => write!(f, "{}", crate::scene::USAGE),
+            Error::RenderState => write!(
+                f,
+                "Animation::set_default_camera must be called before you can draw the frame to the screen"
+            ),
+            Error::CameraNotSet => write!(
+                f,
+                "Animation::set_camera must be called before you can draw into a layer or inset"
+            ),
+            Error::Export(err) => write!(f, "Failed to write the export: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Font { source, .. } | Error::Shader { source, .. } => Some(source),
+            Error::Export(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Export(err)
+    }
+}
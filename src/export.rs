@@ -0,0 +1,322 @@
+use std::{
+    borrow::Cow,
+    fs::{self, File},
+    io::{self, BufWriter},
+    ops::Range,
+    path::PathBuf,
+};
+
+use color_quant::NeuQuant;
+use macroquad::prelude::*;
+
+use crate::motion_blur::MotionBlur;
+
+const GIF_MAX_COLORS: usize = 256;
+const RESERVED_INDEX: u8 = (GIF_MAX_COLORS - 1) as u8;
+
+pub enum ExportFormat {
+    PngSequence {
+        dir: PathBuf,
+    },
+    Gif {
+        path: PathBuf,
+        frame_delay: f32, // Seconds between frames, stored by the gif format in 1/100ths
+        quantize_speed: i32, // 1 (best quality) -> 30 (fastest)
+    },
+}
+
+pub struct Export {
+    format: ExportFormat,
+    frame_range: Option<Range<usize>>,
+    motion_blur: Option<MotionBlur>,
+    frames_captured: usize,
+    frames_written: usize,
+    gif_encoder: Option<gif::Encoder<BufWriter<File>>>,
+}
+
+impl Export {
+    pub fn png_sequence(dir: impl Into<PathBuf>) -> Self {
+        Self::new(ExportFormat::PngSequence { dir: dir.into() })
+    }
+
+    pub fn gif(path: impl Into<PathBuf>) -> Self {
+        Self::new(ExportFormat::Gif {
+            path: path.into(),
+            frame_delay: 0.04,
+            quantize_speed: 10,
+        })
+    }
+
+    fn new(format: ExportFormat) -> Self {
+        Self {
+            format,
+            frame_range: None,
+            motion_blur: None,
+            frames_captured: 0,
+            frames_written: 0,
+            gif_encoder: None,
+        }
+    }
+
+    pub fn frame_limit(self, frame_limit: usize) -> Self {
+        self.frame_range(0..frame_limit)
+    }
+
+    // Frames are counted from when the export is started, frames before the range are skipped
+    pub fn frame_range(mut self, frame_range: Range<usize>) -> Self {
+        self.frame_range = Some(frame_range);
+        self
+    }
+
+    // Every exported frame averages several frames rendered while the shutter is open. The live
+    // preview is unaffected.
+    pub fn motion_blur(mut self, motion_blur: MotionBlur) -> Self {
+        self.motion_blur = Some(motion_blur);
+        self
+    }
+
+    pub fn frame_delay(mut self, seconds: f32) -> Self {
+        if let ExportFormat::Gif { frame_delay, .. } = &mut self.format {
+            *frame_delay = seconds;
+        }
+        self
+    }
+
+    pub fn quantize_speed(mut self, speed: i32) -> Self {
+        if let ExportFormat::Gif { quantize_speed, .. } = &mut self.format {
+            *quantize_speed = speed.clamp(1, 30);
+        }
+        self
+    }
+
+    pub fn format(&self) -> &ExportFormat {
+        &self.format
+    }
+
+    pub fn frames_written(&self) -> usize {
+        self.frames_written
+    }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(&self.frame_range, Some(range) if self.frames_captured >= range.end)
+    }
+
+    pub(crate) fn shutter(&self) -> Option<MotionBlur> {
+        self.motion_blur
+    }
+
+    pub(crate) fn begin(&mut self) -> io::Result<()> {
+        match &self.format {
+            ExportFormat::PngSequence { dir } => fs::create_dir_all(dir),
+            ExportFormat::Gif { path, .. } => match path.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => fs::create_dir_all(parent),
+                _ => Ok(()),
+            },
+        }
+    }
+
+    // A background of None means the frame is transparent and its alpha is kept
+    pub(crate) fn write_frame(
+        &mut self,
+        texture: &Texture2D,
+        background: Option<Color>,
+    ) -> io::Result<()> {
+        if self.is_finished() {
+            return Ok(());
+        }
+
+        let frame_index = self.frames_captured;
+        self.frames_captured += 1;
+        if matches!(&self.frame_range, Some(range) if frame_index < range.start) {
+            return Ok(());
+        }
+
+        // The render target is drawn with a flipped y axis, so the rows read back from the gpu
+        // are already in top to bottom order and don't need flipping like Image::export_png does
+        let mut frame = texture.get_texture_data();
+        if background.is_none() {
+            unpremultiply(&mut frame.bytes);
+        }
+
+        match &self.format {
+            ExportFormat::PngSequence { dir } => {
+                let path = dir.join(format!("frame_{:05}.png", self.frames_written + 1));
+                let (bytes, color_type) = if background.is_some() {
+                    // An opaque frame, so the alpha channel is dropped
+                    let rgb = frame
+                        .bytes
+                        .chunks_exact(4)
+                        .flat_map(|pixel| [pixel[0], pixel[1], pixel[2]])
+                        .collect();
+                    (rgb, image::ColorType::Rgb8)
+                } else {
+                    (frame.bytes, image::ColorType::Rgba8)
+                };
+
+                image::save_buffer(
+                    path,
+                    &bytes,
+                    frame.width as u32,
+                    frame.height as u32,
+                    color_type,
+                )
+                .map_err(io::Error::other)?;
+            }
+            ExportFormat::Gif {
+                path,
+                frame_delay,
+                quantize_speed,
+            } => {
+                if self.gif_encoder.is_none() {
+                    let file = BufWriter::new(File::create(path)?);
+                    let mut encoder = gif::Encoder::new(file, frame.width, frame.height, &[])
+                        .map_err(io::Error::other)?;
+                    encoder
+                        .set_repeat(gif::Repeat::Infinite)
+                        .map_err(io::Error::other)?;
+                    self.gif_encoder = Some(encoder);
+                }
+
+                let (palette, indices) = quantize(&frame.bytes, background, *quantize_speed);
+                let gif_frame = gif::Frame {
+                    width: frame.width,
+                    height: frame.height,
+                    delay: (frame_delay * 100.).round() as u16,
+                    // Transparent pixels would otherwise show the previous frame through them
+                    dispose: gif::DisposalMethod::Background,
+                    transparent: background.is_none().then_some(RESERVED_INDEX),
+                    palette: Some(palette),
+                    buffer: Cow::Owned(indices),
+                    ..Default::default()
+                };
+
+                if let Some(encoder) = &mut self.gif_encoder {
+                    encoder.write_frame(&gif_frame).map_err(io::Error::other)?;
+                }
+            }
+        }
+
+        self.frames_written += 1;
+        Ok(())
+    }
+
+    pub(crate) fn finish(&mut self) -> io::Result<()> {
+        if let Some(encoder) = self.gif_encoder.take() {
+            // Writes the gif trailer
+            encoder.into_inner()?.into_inner()?;
+        }
+
+        Ok(())
+    }
+}
+
+// Builds a palette for the frame where the last entry is reserved for the background. With a
+// background colour it is exactly that colour, without one it is the transparent colour used for
+// every pixel less than half opaque. Returns the rgb palette and the palette index of every pixel.
+fn quantize(rgba: &[u8], background: Option<Color>, speed: i32) -> (Vec<u8>, Vec<u8>) {
+    let bg = background.map_or([0; 3], |bg_color| {
+        [
+            (bg_color.r * 255.).round() as u8,
+            (bg_color.g * 255.).round() as u8,
+            (bg_color.b * 255.).round() as u8,
+        ]
+    });
+    let is_bg = |pixel: &[u8]| match background {
+        Some(_) => pixel[..3]
+            .iter()
+            .zip(bg)
+            .all(|(channel, bg_channel)| channel.abs_diff(bg_channel) <= 1),
+        None => pixel[3] < 128,
+    };
+
+    let neu_quant = NeuQuant::new(speed, GIF_MAX_COLORS - 1, rgba);
+    let mut palette = neu_quant.color_map_rgb();
+    palette.resize((GIF_MAX_COLORS - 1) * 3, 0);
+    palette.extend_from_slice(&bg);
+
+    let indices = rgba
+        .chunks_exact(4)
+        .map(|pixel| {
+            if is_bg(pixel) {
+                RESERVED_INDEX
+            } else {
+                neu_quant.index_of(pixel) as u8
+            }
+        })
+        .collect();
+
+    (palette, indices)
+}
+
+// The render target holds premultiplied colours, image formats expect straight alpha
+fn unpremultiply(rgba: &mut [u8]) {
+    for pixel in rgba.chunks_exact_mut(4) {
+        let alpha = pixel[3] as u32;
+        if alpha == 0 {
+            pixel[..3].fill(0);
+        } else if alpha < 255 {
+            for channel in &mut pixel[..3] {
+                *channel = ((*channel as u32 * 255 + alpha / 2) / alpha).min(255) as u8;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Repeated so the quantizer has enough pixels to learn every colour
+    fn pixels(colors: &[[u8; 4]]) -> Vec<u8> {
+        colors
+            .iter()
+            .flatten()
+            .copied()
+            .cycle()
+            .take(colors.len() * 4 * 256)
+            .collect()
+    }
+
+    #[test]
+    fn quantizing_reserves_the_background() {
+        let background = Color::from_rgba(40, 40, 40, 255);
+        let rgba = pixels(&[
+            [40, 40, 40, 255],
+            [255, 0, 0, 255],
+            [41, 39, 40, 255],
+            [0, 0, 255, 255],
+        ]);
+        let (palette, indices) = quantize(&rgba, Some(background), 10);
+        assert_eq!(palette.len(), GIF_MAX_COLORS * 3);
+        assert_eq!(palette[palette.len() - 3..], [40, 40, 40]);
+        assert_eq!(indices[0], RESERVED_INDEX);
+        // Off by one from rounding still counts as background
+        assert_eq!(indices[2], RESERVED_INDEX);
+        assert!(indices[1] != RESERVED_INDEX && indices[3] != RESERVED_INDEX);
+        assert_ne!(indices[1], indices[3]);
+
+        let red = indices[1] as usize * 3;
+        assert!(palette[red] > 250 && palette[red + 1] < 5 && palette[red + 2] < 5);
+    }
+
+    #[test]
+    fn quantizing_without_a_background_keeps_mostly_opaque_pixels() {
+        let rgba = pixels(&[[0, 0, 0, 0], [255, 0, 0, 127], [255, 0, 0, 128]]);
+        let (_, indices) = quantize(&rgba, None, 10);
+        assert_eq!(indices[..2], [RESERVED_INDEX, RESERVED_INDEX]);
+        assert_ne!(indices[2], RESERVED_INDEX);
+    }
+
+    #[test]
+    fn unpremultiplies_translucent_pixels() {
+        let mut rgba = vec![
+            128, 64, 0, 128, 10, 20, 30, 0, 10, 20, 30, 255, 200, 0, 0, 100,
+        ];
+        unpremultiply(&mut rgba);
+        assert_eq!(rgba[..4], [255, 128, 0, 128]);
+        assert_eq!(rgba[4..8], [0, 0, 0, 0]);
+        assert_eq!(rgba[8..12], [10, 20, 30, 255]);
+        // Colours brighter than their alpha allows are clamped
+        assert_eq!(rgba[12..], [255, 0, 0, 100]);
+    }
+}
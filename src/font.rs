@@ -0,0 +1,109 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use macroquad::prelude::*;
+
+use crate::error::Error;
+
+pub const DEFAULT_FONT_NAME: &str = "Droid Sans Mono";
+
+static FONTS: Mutex<FontRegistry> = Mutex::new(FontRegistry {
+    fonts: Vec::new(),
+    default: None,
+});
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct FontHandle(usize);
+
+struct FontRegistry {
+    // The bytes are kept for reading glyph outlines
+    fonts: Vec<(String, Font, Arc<[u8]>)>,
+    default: Option<FontHandle>,
+}
+
+impl FontRegistry {
+    fn insert(&mut self, name: &str, font: Font, bytes: Arc<[u8]>) -> FontHandle {
+        // Loading a font under an existing name replaces it, so handles to it stay valid
+        if let Some(index) = self.fonts.iter().position(|(other, ..)| other == name) {
+            self.fonts[index] = (name.to_owned(), font, bytes);
+            return FontHandle(index);
+        }
+
+        self.fonts.push((name.to_owned(), font, bytes));
+        FontHandle(self.fonts.len() - 1)
+    }
+}
+
+fn registry() -> MutexGuard<'static, FontRegistry> {
+    FONTS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+// Loads a TTF or OTF font. The first font loaded becomes the default.
+pub fn load_font_from_bytes(name: &str, bytes: &[u8]) -> Result<FontHandle, Error> {
+    let font = load_ttf_font_from_bytes(bytes).map_err(|source| Error::Font {
+        name: name.to_owned(),
+        source,
+    })?;
+
+    let mut registry = registry();
+    let handle = registry.insert(name, font, bytes.into());
+    registry.default.get_or_insert(handle);
+    Ok(handle)
+}
+
+pub async fn load_font(name: &str, path: &str) -> Result<FontHandle, Error> {
+    let bytes = load_file(path).await.map_err(|source| Error::Font {
+        name: name.to_owned(),
+        source,
+    })?;
+    load_font_from_bytes(name, &bytes)
+}
+
+pub fn font(name: &str) -> Option<FontHandle> {
+    registry()
+        .fonts
+        .iter()
+        .position(|(other, ..)| other == name)
+        .map(FontHandle)
+}
+
+pub fn default_font() -> Option<FontHandle> {
+    registry().default
+}
+
+pub fn set_default_font(handle: FontHandle) {
+    registry().default = Some(handle);
+}
+
+// Resolves a handle to the loaded font, None picks the default font
+pub fn get_font(handle: Option<FontHandle>) -> Option<Font> {
+    let registry = registry();
+    let handle = handle.or(registry.default)?;
+    registry
+        .fonts
+        .get(handle.0)
+        .map(|(_, font, _)| font.clone())
+}
+
+// The font file a handle was loaded from, None picks the default font
+pub(crate) fn get_font_bytes(handle: Option<FontHandle>) -> Option<Arc<[u8]>> {
+    let registry = registry();
+    let handle = handle.or(registry.default)?;
+    registry
+        .fonts
+        .get(handle.0)
+        .map(|(_, _, bytes)| Arc::clone(bytes))
+}
+
+// Makes sure the font bundled with mqanim is available. Safe to call any number of times.
+pub(crate) fn load_builtin_font() -> Result<(), Error> {
+    if font(DEFAULT_FONT_NAME).is_none() {
+        load_font_from_bytes(
+            DEFAULT_FONT_NAME,
+            include_bytes!("./font/Droid Sans Mono.ttf"),
+        )?;
+    }
+
+    Ok(())
+}
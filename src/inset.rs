@@ -0,0 +1,372 @@
+use macroquad::prelude::*;
+
+use crate::{blend::premultiplied_material, camera::CameraView, error::Error, Animation};
+
+// Thicknesses are in frame units. The defaults of 2 and 1 are logical pixels at the default
+// world size, for_animation keeps them pixels in any world.
+#[derive(Copy, Clone)]
+pub struct InsetStyle {
+    pub border_thickness: f32, // 0 for no border
+    pub border_color: Color,
+    // Outlines the source region and joins it to the inset with two lines
+    pub connectors: bool,
+    pub connector_thickness: f32,
+    pub connector_color: Color,
+}
+
+impl Default for InsetStyle {
+    fn default() -> Self {
+        Self::with_units_per_pixel(1.)
+    }
+}
+
+impl InsetStyle {
+    pub fn for_animation(animation: &Animation) -> Self {
+        Self::with_units_per_pixel(animation.units_per_pixel())
+    }
+
+    fn with_units_per_pixel(units_per_pixel: f32) -> Self {
+        Self {
+            border_thickness: 2. * units_per_pixel,
+            border_color: WHITE,
+            connectors: true,
+            connector_thickness: units_per_pixel,
+            connector_color: LIGHTGRAY,
+        }
+    }
+}
+
+// Shows a region of the world magnified in a rectangle of the frame. The source is in world
+// coordinates, the destination in frame coordinates: the world coordinates of the default view,
+// unaffected by moving the camera. Both rects are given by their bottom left corner.
+pub struct Inset {
+    source: Rect,
+    dest: Rect,
+    style: InsetStyle,
+}
+
+impl Inset {
+    pub fn new(source: Rect, dest: Rect) -> Self {
+        Self {
+            source,
+            dest,
+            style: InsetStyle::default(),
+        }
+    }
+
+    pub fn style(mut self, style: InsetStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn source(&self) -> Rect {
+        self.source
+    }
+
+    // Moving the source animates what the inset is looking at
+    pub fn set_source(&mut self, source: Rect) {
+        self.source = source;
+    }
+
+    pub fn dest(&self) -> Rect {
+        self.dest
+    }
+
+    pub fn set_dest(&mut self, dest: Rect) {
+        self.dest = dest;
+    }
+
+    pub fn style_mut(&mut self) -> &mut InsetStyle {
+        &mut self.style
+    }
+
+    // Fits the source into the destination, keeping its aspect ratio. Fails when either has no
+    // area.
+    pub(crate) fn view(&self) -> Result<CameraView, Error> {
+        if !(self.dest.w > 0. && self.dest.h > 0.) {
+            return Err(Error::InvalidViewRect {
+                width: self.dest.w,
+                height: self.dest.h,
+            });
+        }
+        CameraView::fit(self.source, self.dest.size())
+    }
+}
+
+struct InsetTarget {
+    name: String,
+    inset: Inset,
+    target: Option<RenderTarget>,
+    // Whether the inset was drawn into this frame, the target still holds older frames otherwise
+    rendered: bool,
+}
+
+#[derive(Default)]
+pub(crate) struct InsetStack {
+    insets: Vec<InsetTarget>,
+    material: Option<Material>,
+    target: Option<RenderTarget>,
+}
+
+impl InsetStack {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.insets.is_empty()
+    }
+
+    pub(crate) fn names(&self) -> Vec<String> {
+        self.insets.iter().map(|inset| inset.name.clone()).collect()
+    }
+
+    pub(crate) fn add(&mut self, name: &str, inset: Inset) -> Result<(), Error> {
+        if self.material.is_none() {
+            self.material = Some(premultiplied_material()?);
+        }
+
+        self.remove(name);
+        self.insets.push(InsetTarget {
+            name: name.to_owned(),
+            inset,
+            target: None,
+            rendered: false,
+        });
+        Ok(())
+    }
+
+    // Render targets aren't freed when dropped, so removed insets delete theirs
+    pub(crate) fn remove(&mut self, name: &str) {
+        self.insets.retain(|inset| {
+            if inset.name == name {
+                if let Some(target) = &inset.target {
+                    target.delete();
+                }
+            }
+            inset.name != name
+        });
+    }
+
+    pub(crate) fn get(&self, name: &str) -> Option<&Inset> {
+        self.insets
+            .iter()
+            .find(|inset| inset.name == name)
+            .map(|inset| &inset.inset)
+    }
+
+    pub(crate) fn get_mut(&mut self, name: &str) -> Option<&mut Inset> {
+        self.insets
+            .iter_mut()
+            .find(|inset| inset.name == name)
+            .map(|inset| &mut inset.inset)
+    }
+
+    // Insets that aren't drawn into again before the frame is drawn are left out of it
+    pub(crate) fn begin_frame(&mut self) {
+        for inset in &mut self.insets {
+            inset.rendered = false;
+        }
+    }
+
+    // The camera drawing the scene into the inset, with its render target sized for
+    // pixels_per_unit render pixels per frame unit
+    pub(crate) fn camera(&mut self, name: &str, pixels_per_unit: f32) -> Result<Camera2D, Error> {
+        let inset = self
+            .insets
+            .iter_mut()
+            .find(|inset| inset.name == name)
+            .ok_or_else(|| Error::UnknownInset(name.to_owned()))?;
+        let view = inset.inset.view()?;
+
+        let size = (inset.inset.dest.size() * pixels_per_unit)
+            .round()
+            .max(vec2(1., 1.));
+        if let Some(target) = inset.target.take_if(|target| target.texture.size() != size) {
+            target.delete();
+        }
+        let target = inset
+            .target
+            .get_or_insert_with(|| render_target(size.x as u32, size.y as u32));
+        inset.rendered = true;
+
+        let mut camera = Camera2D {
+            render_target: Some(target.clone()),
+            ..Default::default()
+        };
+        view.apply(&mut camera, inset.inset.dest.size());
+        Ok(camera)
+    }
+
+    // Draws the insets, their borders and connectors over the scene. world_to_frame maps world
+    // coordinates through the scene camera into frame coordinates.
+    pub(crate) fn draw_over(
+        &mut self,
+        scene: &Texture2D,
+        frame_size: Vec2,
+        filter_mode: FilterMode,
+        world_to_frame: impl Fn(Vec2) -> Vec2,
+    ) -> Texture2D {
+        let size = scene.size();
+        if let Some(target) = self.target.take_if(|target| target.texture.size() != size) {
+            target.delete();
+        }
+        let target = self
+            .target
+            .get_or_insert_with(|| render_target(size.x as u32, size.y as u32));
+        target.texture.set_filter(filter_mode);
+
+        let Some(material) = &self.material else {
+            return scene.clone();
+        };
+        gl_use_material(material);
+
+        // Copies the scene keeping the first row at the top
+        set_camera(&Camera2D {
+            target: size / 2.,
+            zoom: vec2(2. / size.x, 2. / size.y),
+            render_target: Some(target.clone()),
+            ..Default::default()
+        });
+        clear_background(Color::new(0., 0., 0., 0.));
+        draw_texture_ex(
+            scene,
+            0.,
+            0.,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(size),
+                ..Default::default()
+            },
+        );
+
+        // Frame coordinates, the same orientation as the scene camera
+        set_camera(&Camera2D {
+            zoom: vec2(2. / frame_size.x, -2. / frame_size.y),
+            render_target: Some(target.clone()),
+            ..Default::default()
+        });
+
+        for InsetTarget {
+            inset,
+            target: inset_target,
+            rendered,
+            ..
+        } in &self.insets
+        {
+            let Some(inset_target) = inset_target.as_ref().filter(|_| *rendered) else {
+                continue;
+            };
+            let dest = inset.dest;
+            let style = inset.style;
+
+            if style.connectors {
+                let source = inset.source;
+                let corners = [
+                    vec2(source.x, source.y),
+                    vec2(source.x + source.w, source.y),
+                    vec2(source.x + source.w, source.y + source.h),
+                    vec2(source.x, source.y + source.h),
+                ]
+                .map(&world_to_frame);
+                for (index, corner) in corners.iter().enumerate() {
+                    let next = corners[(index + 1) % 4];
+                    draw_line(
+                        corner.x,
+                        corner.y,
+                        next.x,
+                        next.y,
+                        style.connector_thickness,
+                        premultiply(style.connector_color),
+                    );
+                }
+
+                for (from, to) in connector_lines(&corners, dest) {
+                    draw_line(
+                        from.x,
+                        from.y,
+                        to.x,
+                        to.y,
+                        style.connector_thickness,
+                        premultiply(style.connector_color),
+                    );
+                }
+            }
+
+            // The inset is rendered with its first row at the top, which is up in frame
+            // coordinates
+            draw_texture_ex(
+                &inset_target.texture,
+                dest.x,
+                dest.y,
+                WHITE,
+                DrawTextureParams {
+                    dest_size: Some(dest.size()),
+                    flip_y: true,
+                    ..Default::default()
+                },
+            );
+
+            if style.border_thickness > 0. {
+                draw_rectangle_lines(
+                    dest.x,
+                    dest.y,
+                    dest.w,
+                    dest.h,
+                    style.border_thickness,
+                    premultiply(style.border_color),
+                );
+            }
+        }
+
+        gl_use_default_material();
+        set_default_camera();
+        target.texture.clone()
+    }
+}
+
+// Everything here is drawn with premultiplied blending
+fn premultiply(color: Color) -> Color {
+    Color::new(
+        color.r * color.a,
+        color.g * color.a,
+        color.b * color.a,
+        color.a,
+    )
+}
+
+// Joins the two corners of the source facing the inset to the two nearest corners of the inset,
+// picked by which side of the source the inset is on
+fn connector_lines(source_corners: &[Vec2; 4], dest: Rect) -> [(Vec2, Vec2); 2] {
+    let min = source_corners
+        .iter()
+        .fold(source_corners[0], |min, corner| min.min(*corner));
+    let max = source_corners
+        .iter()
+        .fold(source_corners[0], |max, corner| max.max(*corner));
+
+    let offset = dest.center() - (min + max) / 2.;
+    let (left, right, bottom, top) = (dest.x, dest.x + dest.w, dest.y, dest.y + dest.h);
+
+    // Compared relative to the combined size so wide and tall layouts both pick the right side
+    let extent = (max - min) + dest.size();
+    if offset.x.abs() / extent.x >= offset.y.abs() / extent.y {
+        if offset.x > 0. {
+            [
+                (vec2(max.x, max.y), vec2(left, top)),
+                (vec2(max.x, min.y), vec2(left, bottom)),
+            ]
+        } else {
+            [
+                (vec2(min.x, max.y), vec2(right, top)),
+                (vec2(min.x, min.y), vec2(right, bottom)),
+            ]
+        }
+    } else if offset.y > 0. {
+        [
+            (vec2(min.x, max.y), vec2(left, bottom)),
+            (vec2(max.x, max.y), vec2(right, bottom)),
+        ]
+    } else {
+        [
+            (vec2(min.x, min.y), vec2(left, top)),
+            (vec2(max.x, min.y), vec2(right, top)),
+        ]
+    }
+}
@@ -0,0 +1,276 @@
+use std::{fmt, fs, io, ops::Range, path::Path};
+
+use macroquad::prelude::*;
+
+use crate::{easing::Easing, tween::Lerp};
+
+#[derive(Clone)]
+pub struct Keyframe<T> {
+    pub time: f32,
+    pub value: T,
+    // Easing used when interpolating from this keyframe to the next one
+    pub easing: Easing,
+}
+
+#[derive(Clone)]
+pub struct Track<T> {
+    keyframes: Vec<Keyframe<T>>,
+}
+
+impl<T> Default for Track<T> {
+    fn default() -> Self {
+        Self {
+            keyframes: Vec::new(),
+        }
+    }
+}
+
+impl<T: Lerp + Clone> Track<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn keyframe(self, time: f32, value: T) -> Self {
+        self.keyframe_eased(time, value, Easing::Linear)
+    }
+
+    pub fn keyframe_eased(mut self, time: f32, value: T, easing: Easing) -> Self {
+        self.push(Keyframe {
+            time,
+            value,
+            easing,
+        });
+        self
+    }
+
+    // Keyframes are kept sorted by time, a keyframe at the same time as another goes after it
+    pub fn push(&mut self, keyframe: Keyframe<T>) {
+        let index = self
+            .keyframes
+            .partition_point(|other| other.time <= keyframe.time);
+        self.keyframes.insert(index, keyframe);
+    }
+
+    pub fn keyframes(&self) -> &[Keyframe<T>] {
+        &self.keyframes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keyframes.is_empty()
+    }
+
+    // The time of the last keyframe
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map_or(0., |keyframe| keyframe.time)
+    }
+
+    // Holds the first value before the first keyframe and the last value after the last keyframe.
+    // None if the track has no keyframes.
+    pub fn sample(&self, time: f32) -> Option<T> {
+        let next = self
+            .keyframes
+            .partition_point(|keyframe| keyframe.time <= time);
+
+        if next == 0 {
+            return self
+                .keyframes
+                .first()
+                .map(|keyframe| keyframe.value.clone());
+        }
+        if next == self.keyframes.len() {
+            return self.keyframes.last().map(|keyframe| keyframe.value.clone());
+        }
+
+        let from = &self.keyframes[next - 1];
+        let to = &self.keyframes[next];
+        let t = (time - from.time) / (to.time - from.time);
+        Some(from.value.lerp(&to.value, from.easing.ease(t)))
+    }
+}
+
+// A value that can be read from the components written on a line of a track file
+pub trait TrackValue: Sized {
+    const COMPONENTS: usize;
+
+    fn from_components(components: &[f32]) -> Self;
+}
+
+impl TrackValue for f32 {
+    const COMPONENTS: usize = 1;
+
+    fn from_components(components: &[f32]) -> Self {
+        components[0]
+    }
+}
+
+impl TrackValue for Vec2 {
+    const COMPONENTS: usize = 2;
+
+    fn from_components(components: &[f32]) -> Self {
+        vec2(components[0], components[1])
+    }
+}
+
+impl TrackValue for Color {
+    const COMPONENTS: usize = 4;
+
+    fn from_components(components: &[f32]) -> Self {
+        Color::new(components[0], components[1], components[2], components[3])
+    }
+}
+
+impl TrackValue for Range<f32> {
+    const COMPONENTS: usize = 2;
+
+    fn from_components(components: &[f32]) -> Self {
+        components[0]..components[1]
+    }
+}
+
+#[derive(Debug)]
+pub enum TrackError {
+    Io(io::Error),
+    Parse { line: usize, message: String },
+}
+
+impl fmt::Display for TrackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrackError::Io(err) => write!(f, "Failed to read the track file: {err}"),
+            TrackError::Parse { line, message } => write!(f, "Line {line}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for TrackError {}
+
+impl From<io::Error> for TrackError {
+    fn from(err: io::Error) -> Self {
+        TrackError::Io(err)
+    }
+}
+
+impl<T: TrackValue + Lerp + Clone> Track<T> {
+    // Every non empty line that doesn't start with # is a keyframe written as:
+    //     <time> <value components...> [easing]
+    // e.g. for a Vec2 track "1.5 100 -20 cubic_in_out". The easing defaults to linear.
+    pub fn parse(text: &str) -> Result<Self, TrackError> {
+        let mut track = Self::new();
+
+        for (line_index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let parse_error = |message: String| TrackError::Parse {
+                line: line_index + 1,
+                message,
+            };
+
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            if tokens.len() < T::COMPONENTS + 1 {
+                return Err(parse_error(format!(
+                    "Expected a time followed by {} value components",
+                    T::COMPONENTS
+                )));
+            }
+
+            let numbers = tokens[..T::COMPONENTS + 1]
+                .iter()
+                .map(|token| token.parse::<f32>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|err| parse_error(format!("Invalid number: {err}")))?;
+
+            let easing = if tokens.len() > T::COMPONENTS + 1 {
+                tokens[T::COMPONENTS + 1..]
+                    .join(" ")
+                    .parse::<Easing>()
+                    .map_err(parse_error)?
+            } else {
+                Easing::Linear
+            };
+
+            track.push(Keyframe {
+                time: numbers[0],
+                value: T::from_components(&numbers[1..]),
+                easing,
+            });
+        }
+
+        Ok(track)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, TrackError> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn samples_between_keyframes_with_their_easing() {
+        let track = Track::new()
+            .keyframe(2., 10.)
+            .keyframe_eased(0., 0., Easing::QuadIn)
+            .keyframe(3., 0.);
+        assert_eq!(track.duration(), 3.);
+        assert_eq!(track.sample(-1.), Some(0.));
+        assert_eq!(track.sample(1.), Some(2.5));
+        assert_eq!(track.sample(2.), Some(10.));
+        assert_eq!(track.sample(2.5), Some(5.));
+        assert_eq!(track.sample(4.), Some(0.));
+        assert_eq!(Track::<f32>::new().sample(1.), None);
+    }
+
+    #[test]
+    fn keyframes_at_the_same_time_jump() {
+        let track = Track::new()
+            .keyframe(0., 0.)
+            .keyframe(1., 5.)
+            .keyframe(1., 10.)
+            .keyframe(2., 20.);
+        assert_eq!(track.sample(0.5), Some(2.5));
+        assert_eq!(track.sample(1.), Some(10.));
+        assert_eq!(track.sample(1.5), Some(15.));
+    }
+
+    #[test]
+    fn parses_values_easings_and_comments() {
+        let track = Track::<Vec2>::parse(
+            "# position\n\
+             0 0 0\n\
+             \n\
+             1.5 100 -20 cubic_bezier(0.4, 0, 0.2, 1)\n\
+             1 10 10 quad_out",
+        )
+        .unwrap();
+        let keyframes = track.keyframes();
+        assert_eq!(keyframes.len(), 3);
+        assert_eq!(keyframes[1].time, 1.);
+        assert!(matches!(keyframes[1].easing, Easing::QuadOut));
+        assert!(matches!(keyframes[2].easing, Easing::CubicBezier(..)));
+        assert_eq!(keyframes[2].value, vec2(100., -20.));
+
+        let color = Track::<Color>::parse("0 1 0.5 0 1").unwrap();
+        assert_eq!(color.sample(0.), Some(Color::new(1., 0.5, 0., 1.)));
+    }
+
+    #[test]
+    fn parse_errors_name_the_line() {
+        let error = |text: &str| match Track::<Vec2>::parse(text) {
+            Err(TrackError::Parse { line, message }) => (line, message),
+            _ => panic!("{text} should not parse"),
+        };
+        assert_eq!(error("0 0 0\n1 5").0, 2);
+        let (line, message) = error("# comment\n\n0 0 x");
+        assert_eq!(line, 3);
+        assert!(message.starts_with("Invalid number"));
+        assert_eq!(
+            error("0 0 0 wobble"),
+            (1, "Unknown easing wobble".to_owned())
+        );
+    }
+}
@@ -0,0 +1,213 @@
+use macroquad::prelude::*;
+
+use crate::{blend::BlendMode, error::Error};
+
+pub struct Layer {
+    name: String,
+    z_index: i32,
+    opacity: f32,
+    visible: bool,
+    blend_mode: BlendMode,
+    target: RenderTarget,
+}
+
+impl Layer {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    // Layers are composited from the lowest to the highest z index. Whatever is drawn outside of a
+    // layer is at z index 0, under layers that are also at 0.
+    pub fn z_index(&self) -> i32 {
+        self.z_index
+    }
+
+    pub fn set_z_index(&mut self, z_index: i32) {
+        self.z_index = z_index;
+    }
+
+    pub fn opacity(&self) -> f32 {
+        self.opacity
+    }
+
+    pub fn set_opacity(&mut self, opacity: f32) {
+        self.opacity = opacity.clamp(0., 1.);
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    pub fn blend_mode(&self) -> BlendMode {
+        self.blend_mode
+    }
+
+    pub fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.blend_mode = blend_mode;
+    }
+
+    // The scene camera drawing into this layer instead
+    pub(crate) fn camera(&self, scene_camera: &Camera2D) -> Camera2D {
+        Camera2D {
+            rotation: scene_camera.rotation,
+            zoom: scene_camera.zoom,
+            target: scene_camera.target,
+            offset: scene_camera.offset,
+            render_target: Some(self.target.clone()),
+            viewport: scene_camera.viewport,
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct LayerStack {
+    layers: Vec<Layer>,
+    // One material per blend mode, in the order of BlendMode::ALL
+    materials: Vec<Vec<Material>>,
+    target: Option<RenderTarget>,
+}
+
+impl LayerStack {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    // Adding a layer with the name of an existing layer replaces it
+    pub(crate) fn add(
+        &mut self,
+        name: &str,
+        z_index: i32,
+        size: UVec2,
+        filter_mode: FilterMode,
+    ) -> Result<(), Error> {
+        if self.materials.is_empty() {
+            self.materials = BlendMode::ALL
+                .iter()
+                .map(BlendMode::materials)
+                .collect::<Result<_, _>>()?;
+        }
+
+        self.remove(name);
+        let target = render_target(size.x, size.y);
+        target.texture.set_filter(filter_mode);
+        self.layers.push(Layer {
+            name: name.to_owned(),
+            z_index,
+            opacity: 1.,
+            visible: true,
+            blend_mode: BlendMode::Normal,
+            target,
+        });
+        Ok(())
+    }
+
+    // Render targets aren't freed when dropped, so removed layers delete theirs
+    pub(crate) fn remove(&mut self, name: &str) {
+        self.layers.retain(|layer| {
+            if layer.name == name {
+                layer.target.delete();
+            }
+            layer.name != name
+        });
+    }
+
+    pub(crate) fn get(&self, name: &str) -> Option<&Layer> {
+        self.layers.iter().find(|layer| layer.name == name)
+    }
+
+    pub(crate) fn get_mut(&mut self, name: &str) -> Option<&mut Layer> {
+        self.layers.iter_mut().find(|layer| layer.name == name)
+    }
+
+    // Layers always match the size and filter mode of the scene render target
+    pub(crate) fn resize(&mut self, size: UVec2, filter_mode: FilterMode) {
+        for layer in &mut self.layers {
+            layer.target.delete();
+            layer.target = render_target(size.x, size.y);
+            layer.target.texture.set_filter(filter_mode);
+        }
+    }
+
+    pub(crate) fn set_filter(&self, filter_mode: FilterMode) {
+        for layer in &self.layers {
+            layer.target.texture.set_filter(filter_mode);
+        }
+    }
+
+    // Clears every layer to transparent for the next frame
+    pub(crate) fn clear(&self, camera: &Camera2D) {
+        for layer in &self.layers {
+            set_camera(&layer.camera(camera));
+            clear_background(Color::new(0., 0., 0., 0.));
+        }
+    }
+
+    // Combines the base scene and the layers over the background, None for a transparent
+    // background
+    pub(crate) fn composite(
+        &mut self,
+        base: &Texture2D,
+        background: Option<Color>,
+        filter_mode: FilterMode,
+    ) -> Texture2D {
+        let size = base.size();
+        if let Some(target) = self.target.take_if(|target| target.texture.size() != size) {
+            target.delete();
+        }
+        let target = self
+            .target
+            .get_or_insert_with(|| render_target(size.x as u32, size.y as u32));
+        target.texture.set_filter(filter_mode);
+
+        // Keeps the first row at the top, the same orientation as the layers
+        set_camera(&Camera2D {
+            target: size / 2.,
+            zoom: vec2(2. / size.x, 2. / size.y),
+            render_target: Some(target.clone()),
+            ..Default::default()
+        });
+        clear_background(background.unwrap_or(Color::new(0., 0., 0., 0.)));
+
+        let mut layers: Vec<&Layer> = self.layers.iter().filter(|layer| layer.visible).collect();
+        // Stable, so the base stays under the layers at z index 0
+        let below = layers.iter().filter(|layer| layer.z_index < 0).count();
+        layers.sort_by_key(|layer| layer.z_index);
+
+        let draw_layer = |texture: &Texture2D, opacity: f32, blend_mode: BlendMode| {
+            let index = BlendMode::ALL
+                .iter()
+                .position(|mode| *mode == blend_mode)
+                .unwrap_or(0);
+            for material in &self.materials[index] {
+                gl_use_material(material);
+                // Premultiplied, so the opacity scales every channel
+                draw_texture_ex(
+                    texture,
+                    0.,
+                    0.,
+                    Color::new(opacity, opacity, opacity, opacity),
+                    DrawTextureParams {
+                        dest_size: Some(size),
+                        ..Default::default()
+                    },
+                );
+            }
+        };
+
+        for layer in &layers[..below] {
+            draw_layer(&layer.target.texture, layer.opacity, layer.blend_mode);
+        }
+        draw_layer(base, 1., BlendMode::Normal);
+        for layer in &layers[below..] {
+            draw_layer(&layer.target.texture, layer.opacity, layer.blend_mode);
+        }
+
+        gl_use_default_material();
+        set_default_camera();
+        target.texture.clone()
+    }
+}
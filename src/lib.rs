@@ -1,14 +1,73 @@
 // use std::cell::OnceCell;
 
-use std::sync::OnceLock;
-
+use std::{cell::Cell, io};
+
+use camera::CameraView;
+use clock::Clock;
+use easing::Easing;
+use error::Error;
+use export::Export;
+use inset::{Inset, InsetStack};
+use layer::{Layer, LayerStack};
 use macroquad::prelude::*;
+use motion_blur::Accumulator;
+use playback::PlaybackControls;
+use post::{PostEffect, PostPass, PostPipeline};
+use scaling::{Letterbox, RenderResolution, ScaleMode};
+use sequence::Action;
+use ssaa::{DownsampleFilter, Supersampler};
+use tween::Lerp;
+pub mod blend;
+pub mod camera;
+pub mod clock;
+pub mod easing;
+pub mod error;
+pub mod export;
+pub mod font;
+pub mod inset;
+pub mod keyframe;
+pub mod layer;
+pub mod morph;
+pub mod motion_blur;
+pub mod path;
+pub mod playback;
 pub mod plot;
+pub mod post;
+pub mod scaling;
+pub mod scene;
+pub mod sequence;
+pub mod shapes;
+pub mod ssaa;
+pub mod tessellate;
+pub mod tween;
 pub mod ui;
 
-static DEFAULT_FONT: OnceLock<Font> = OnceLock::new();
+type DrawScene = Box<dyn FnMut(&Animation)>;
+
+// How text drawn with the camera currently set is sized. Font sizes are in logical pixels, they are
+// converted to world units and rasterized at the render target resolution so text stays sharp.
+#[derive(Copy, Clone)]
+pub(crate) struct TextScale {
+    pub(crate) units_per_pixel: f32,
+    // Render target pixels per logical pixel, including the camera zoom
+    pub(crate) raster_scale: f32,
+}
+
+// How far a world size may stray from the aspect ratio of the frame, relative to it
+const WORLD_ASPECT_EPSILON: f32 = 1e-3;
 
-const RESIZE_HYSTERESIS: f32 = 0.5; // 50% window growth or shrink will cause resize
+const SCREEN_TEXT_SCALE: TextScale = TextScale {
+    units_per_pixel: 1.,
+    raster_scale: 1.,
+};
+
+thread_local! {
+    static TEXT_SCALE: Cell<TextScale> = const { Cell::new(SCREEN_TEXT_SCALE) };
+}
+
+pub(crate) fn text_scale() -> TextScale {
+    TEXT_SCALE.with(Cell::get)
+}
 
 enum RenderState {
     CameraRendering,
@@ -17,20 +76,44 @@ enum RenderState {
 
 pub struct Animation {
     render_target: RenderTarget,
+    // Replaced render targets, deleted once the draws queued into them have been flushed
+    retired_targets: Vec<RenderTarget>,
     camera: Camera2D,
+    view: CameraView,
     bg_color: Color,
+    transparent: bool,
+    scene_material: Material,
+    display_material: Material,
     render_state: RenderState,
-    draw_size: Vec2,
     filter_mode: FilterMode,
-    fxaa_material: Option<Material>,
+    post_pipeline: PostPipeline,
+    layers: LayerStack,
+    insets: InsetStack,
+    supersampler: Option<Supersampler>,
     width: f32,
     height: f32,
-    scale: f32,
-    auto_resize: bool,
+    world_size: Vec2,
+    scale_mode: ScaleMode,
+    letterbox: Letterbox,
+    render_resolution: RenderResolution,
+    export: Option<Export>,
+    accumulator: Option<Accumulator>,
+    sub_frame: u32,
+    clock: Clock,
+    playback: Option<PlaybackControls>,
+    draw_scene: Option<DrawScene>,
 }
 
 impl Animation {
     pub fn new(start_width: f32, start_height: f32, bg_color: Option<Color>) -> Self {
+        Self::try_new(start_width, start_height, bg_color).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_new(
+        start_width: f32,
+        start_height: f32,
+        bg_color: Option<Color>,
+    ) -> Result<Self, Error> {
         // Screen dimensions will be:
         //     x: -width/2 -> width/2 (left -> right)
         //     y: -height/2 -> height/2 (bottom -> top)
@@ -52,34 +135,65 @@ impl Animation {
                 a: 1.,
             }
         };
-        let font = load_ttf_font_from_bytes(include_bytes!("./font/Droid Sans Mono.ttf"))
-            .expect("The Font load failed for Droid Sans Mono ttf.");
+        font::load_builtin_font()?;
 
-        DEFAULT_FONT
-            .set(font)
-            .expect("Failed to set the Default font to Droid Sans Mono");
-
-        Self {
+        Ok(Self {
             render_target,
+            retired_targets: Vec::new(),
             camera,
+            view: CameraView::default(),
             bg_color,
+            transparent: false,
+            scene_material: blend::scene_material()?,
+            display_material: blend::premultiplied_material()?,
             filter_mode: FilterMode::Linear,
             render_state: RenderState::ScreenRendering,
-            draw_size: vec2(start_width, start_height),
-            fxaa_material: None,
+            post_pipeline: PostPipeline::default(),
+            layers: LayerStack::default(),
+            insets: InsetStack::default(),
+            supersampler: None,
             width: start_width,
             height: start_height,
-            scale: Self::compute_scale(start_width, start_height),
-            auto_resize: false,
-        }
+            world_size: vec2(start_width, start_height),
+            scale_mode: ScaleMode::default(),
+            letterbox: Letterbox::default(),
+            render_resolution: RenderResolution::default(),
+            export: None,
+            accumulator: None,
+            sub_frame: 0,
+            clock: Clock::new(),
+            playback: None,
+            draw_scene: None,
+        })
     }
 
+    // Renders at the size the frame is drawn in the window
     pub fn enable_auto_resize(&mut self) {
-        self.auto_resize = true;
+        self.set_render_resolution(RenderResolution::Window);
+    }
+
+    pub fn render_resolution(&self) -> RenderResolution {
+        self.render_resolution
+    }
+
+    pub fn set_render_resolution(&mut self, render_resolution: RenderResolution) {
+        self.render_resolution = render_resolution;
+        self.resize_render_target();
+    }
+
+    // The pixel size of the rendered frame, before supersampling
+    pub fn render_size(&self) -> UVec2 {
+        let size = match self.render_resolution {
+            RenderResolution::Logical => vec2(self.width, self.height),
+            RenderResolution::Fixed { width, height } => vec2(width as f32, height as f32),
+            RenderResolution::Window => self.frame_rect().size(),
+        };
+        size.round().as_uvec2().max(uvec2(1, 1))
     }
     pub fn filter_mode(&mut self, filter_mode: FilterMode) {
         self.filter_mode = filter_mode;
         self.render_target.texture.set_filter(filter_mode);
+        self.layers.set_filter(filter_mode);
     }
 
     pub fn get_world_mouse(&self) -> Vec2 {
@@ -88,371 +202,643 @@ impl Animation {
     }
 
     pub fn screen_to_world(&self, point: Vec2) -> Vec2 {
-        // Mouse position in the virtual screen
-        Vec2 {
-            x: ((point.x - (screen_width() - (self.width * self.scale)) * 0.5) / self.scale)
-                - 0.5 * self.width,
-            y: 0.5 * self.height
-                - (point.y - (screen_height() - (self.height * self.scale)) * 0.5) / self.scale,
-        }
+        // Position in the virtual screen
+        let frame_rect = self.frame_rect();
+        let virtual_x = (point.x - frame_rect.x) / frame_rect.w * self.width;
+        let virtual_y = (point.y - frame_rect.y) / frame_rect.h * self.height;
+
+        // The render target is flipped so the top of the virtual screen is at -1
+        let clip = vec3(
+            virtual_x / self.width * 2. - 1.,
+            virtual_y / self.height * 2. - 1.,
+            0.,
+        );
+        self.camera
+            .matrix()
+            .inverse()
+            .transform_point3(clip)
+            .truncate()
     }
 
-    pub fn enable_fxaa(&mut self) {
-        let uniforms = vec![("texture_size".to_string(), UniformType::Float2)];
-        let material = load_material(
-            ShaderSource::Glsl {
-                vertex: FXAA_VERTEX_SHADER,
-                fragment: FXAA_FRAGMENT_SHADER,
-            },
-            MaterialParams {
-                uniforms,
-                ..Default::default()
-            },
+    pub fn world_to_screen(&self, point: Vec2) -> Vec2 {
+        let clip = self
+            .camera
+            .matrix()
+            .transform_point3(vec3(point.x, point.y, 0.));
+        let frame_rect = self.frame_rect();
+
+        vec2(
+            frame_rect.x + (clip.x + 1.) * 0.5 * frame_rect.w,
+            frame_rect.y + (clip.y + 1.) * 0.5 * frame_rect.h,
+        )
+    }
+
+    pub fn scale_mode(&self) -> ScaleMode {
+        self.scale_mode
+    }
+
+    pub fn set_scale_mode(&mut self, scale_mode: ScaleMode) {
+        self.scale_mode = scale_mode;
+    }
+
+    pub fn set_letterbox(&mut self, letterbox: Letterbox) {
+        self.letterbox = letterbox;
+    }
+
+    // The window rect the frame is drawn into with the current scale mode
+    pub fn frame_rect(&self) -> Rect {
+        let logical_size = vec2(self.width, self.height);
+        // Rendering at the window resolution already puts one pixel on every window pixel
+        let pixel_size = match self.render_resolution {
+            RenderResolution::Window => logical_size,
+            _ => self.render_size().as_vec2(),
+        };
+        self.scale_mode.frame_rect(
+            logical_size,
+            pixel_size,
+            vec2(screen_width(), screen_height()),
+        )
+    }
+
+    pub fn view(&self) -> CameraView {
+        self.view
+    }
+
+    // Fails for zooms of zero or less, which can't map the screen back into the world
+    pub fn set_view(&mut self, view: CameraView) -> Result<(), Error> {
+        view.validate()?;
+        self.apply_view(view);
+        Ok(())
+    }
+
+    fn apply_view(&mut self, view: CameraView) {
+        self.view = view;
+        let size = self.size();
+        self.view.apply(&mut self.camera, size);
+    }
+
+    pub fn set_camera_position(&mut self, position: Vec2) {
+        self.apply_view(CameraView {
+            position,
+            ..self.view
+        });
+    }
+
+    pub fn set_camera_zoom(&mut self, zoom: f32) -> Result<(), Error> {
+        self.set_view(CameraView { zoom, ..self.view })
+    }
+
+    pub fn set_camera_rotation(&mut self, rotation: f32) {
+        self.apply_view(CameraView {
+            rotation,
+            ..self.view
+        });
+    }
+
+    // Fails for rects without an area
+    pub fn zoom_to_fit(&mut self, rect: Rect) -> Result<(), Error> {
+        self.set_view(CameraView::fit(rect, self.size())?)
+    }
+
+    // The axis aligned world rect visible with the current view, ignoring rotation
+    pub fn visible_world_rect(&self) -> Rect {
+        let size = self.size() / self.view.zoom;
+        Rect::new(
+            self.view.position.x - size.x / 2.,
+            self.view.position.y - size.y / 2.,
+            size.x,
+            size.y,
         )
-        .unwrap();
+    }
+
+    pub fn enable_fxaa(&mut self) -> Result<(), Error> {
+        self.add_post_pass("fxaa", PostEffect::Fxaa)
+    }
+
+    // Passes run in the order they are added, each one reading the output of the previous.
+    // Fails if the shader of the effect doesn't compile.
+    pub fn add_post_pass(&mut self, name: &str, effect: PostEffect) -> Result<(), Error> {
+        self.post_pipeline.add(name, effect)
+    }
+
+    pub fn remove_post_pass(&mut self, name: &str) {
+        self.post_pipeline.remove(name);
+    }
+
+    // Layers are drawn into between begin_layer and end_layer and composited in z index order.
+    // Whatever is drawn outside of a layer sits at z index 0.
+    pub fn add_layer(&mut self, name: &str, z_index: i32) -> Result<(), Error> {
+        let size = self.render_target.texture.size().as_uvec2();
+        self.layers.add(name, z_index, size, self.filter_mode)
+    }
+
+    pub fn remove_layer(&mut self, name: &str) {
+        self.layers.remove(name);
+    }
+
+    pub fn layer(&self, name: &str) -> Option<&Layer> {
+        self.layers.get(name)
+    }
+
+    pub fn layer_mut(&mut self, name: &str) -> Option<&mut Layer> {
+        self.layers.get_mut(name)
+    }
+
+    pub fn add_inset(&mut self, name: &str, inset: Inset) -> Result<(), Error> {
+        self.insets.add(name, inset)
+    }
+
+    pub fn remove_inset(&mut self, name: &str) {
+        self.insets.remove(name);
+    }
+
+    pub fn inset(&self, name: &str) -> Option<&Inset> {
+        self.insets.get(name)
+    }
+
+    pub fn inset_mut(&mut self, name: &str) -> Option<&mut Inset> {
+        self.insets.get_mut(name)
+    }
+
+    pub fn post_pass(&self, name: &str) -> Option<&PostPass> {
+        self.post_pipeline.get(name)
+    }
+
+    pub fn post_pass_mut(&mut self, name: &str) -> Option<&mut PostPass> {
+        self.post_pipeline.get_mut(name)
+    }
+
+    // Renders the scene at factor times the resolution and filters it back down before post
+    // processing, export and drawing to the screen. World coordinates don't change.
+    pub fn enable_ssaa(&mut self, factor: u32, filter: DownsampleFilter) -> Result<(), Error> {
+        self.supersampler = Some(Supersampler::new(factor, filter)?);
+        self.resize_render_target();
+        Ok(())
+    }
+
+    pub fn disable_ssaa(&mut self) {
+        self.supersampler = None;
+        self.resize_render_target();
+    }
+
+    pub fn start_export(&mut self, mut export: Export) -> Result<(), Error> {
+        export.begin()?;
+        if export.shutter().is_some() && self.accumulator.is_none() {
+            self.accumulator = Some(Accumulator::new()?);
+        }
+        self.sub_frame = 0;
+        self.export = Some(export);
+        Ok(())
+    }
+
+    pub fn stop_export(&mut self) -> io::Result<Option<Export>> {
+        self.sub_frame = 0;
+        if let Some(mut export) = self.export.take() {
+            export.finish()?;
+            return Ok(Some(export));
+        }
+
+        Ok(None)
+    }
+
+    pub fn is_exporting(&self) -> bool {
+        self.export.is_some()
+    }
+
+    pub fn clock(&self) -> &Clock {
+        &self.clock
+    }
+
+    pub fn clock_mut(&mut self) -> &mut Clock {
+        &mut self.clock
+    }
+
+    pub fn time(&self) -> f32 {
+        self.clock.time()
+    }
+
+    pub fn frame(&self) -> u64 {
+        self.clock.frame()
+    }
+
+    pub fn dt(&self) -> f32 {
+        self.clock.dt()
+    }
+
+    // Keyboard controls to pause, step, restart and seek the clock plus a timeline drawn over the
+    // frame. Meant for previewing, exports are never affected.
+    pub fn enable_playback_controls(&mut self, controls: PlaybackControls) {
+        self.playback = Some(controls);
+    }
 
-        self.fxaa_material = Some(material);
+    pub fn disable_playback_controls(&mut self) {
+        self.playback = None;
     }
 
     pub fn set_camera(&mut self) {
-        if self.auto_resize {
-            if self.width > self.draw_size.x * (1. + RESIZE_HYSTERESIS)
-                || self.height > self.draw_size.y * (1. + RESIZE_HYSTERESIS)
-            {
-                self.resize_render_target(
-                    self.width * RESIZE_HYSTERESIS,
-                    self.height * RESIZE_HYSTERESIS,
-                );
-            } else if self.width <= self.draw_size.x * (1. - RESIZE_HYSTERESIS)
-                || self.height <= self.draw_size.y * (1. - RESIZE_HYSTERESIS)
-            {
-                self.resize_render_target(
-                    self.width * 1. / RESIZE_HYSTERESIS,
-                    self.height * 1. / RESIZE_HYSTERESIS,
+        self.tick_clock();
+        self.bind_camera();
+    }
+
+    fn tick_clock(&mut self) {
+        if let Some(playback) = &self.playback {
+            if !self.is_exporting() {
+                playback.handle_input(&mut self.clock);
+            }
+        }
+
+        // Exports always use a fixed timestep so they are reproducible on any machine
+        match self.export.as_ref().and_then(Export::shutter) {
+            Some(motion_blur) => {
+                // Only the first sub frame moves on to the next frame, the rest render the moments
+                // in between while the shutter is open
+                self.clock.tick_sub_frame(
+                    motion_blur.sub_frame_fraction(self.sub_frame),
+                    self.sub_frame == 0,
                 );
             }
+            None => self.clock.tick(self.is_exporting()),
         }
+    }
 
+    fn bind_camera(&mut self) {
+        // The frames that drew into them have been flushed
+        for target in self.retired_targets.drain(..) {
+            target.delete();
+        }
+
+        // Only the window resolution can change by itself
+        if self.render_resolution == RenderResolution::Window
+            && self.render_target.texture.size().as_uvec2()
+                != self.render_size() * self.ssaa_factor()
+        {
+            self.resize_render_target();
+        }
+
+        self.layers.clear(&self.camera);
+        self.insets.begin_frame();
         set_camera(&self.camera);
-        clear_background(self.bg_color);
+        gl_use_material(&self.scene_material);
+        // With layers the background is added when they are composited
+        if self.transparent || !self.layers.is_empty() {
+            clear_background(Color::new(0., 0., 0., 0.));
+        } else {
+            clear_background(self.bg_color);
+        }
         self.render_state = RenderState::CameraRendering;
+
+        self.set_text_scale(&self.camera);
+    }
+
+    // Text drawn with the camera is rasterized at the density of its render target
+    fn set_text_scale(&self, camera: &Camera2D) {
+        let Some(render_target) = &camera.render_target else {
+            return;
+        };
+        let pixels_per_unit = camera.zoom.x.abs() * render_target.texture.width() / 2.;
+        let text_scale = TextScale {
+            units_per_pixel: self.units_per_pixel(),
+            raster_scale: pixels_per_unit * self.units_per_pixel(),
+        };
+        TEXT_SCALE.with(|cell| cell.set(text_scale));
+    }
+
+    // Draws into the layer until end_layer is called, must be called after set_camera
+    pub fn begin_layer(&mut self, name: &str) -> Result<(), Error> {
+        if !matches!(self.render_state, RenderState::CameraRendering) {
+            return Err(Error::CameraNotSet);
+        }
+        let layer = self
+            .layers
+            .get(name)
+            .ok_or_else(|| Error::UnknownLayer(name.to_owned()))?;
+
+        set_camera(&layer.camera(&self.camera));
+        Ok(())
+    }
+
+    // Goes back to drawing outside of any layer
+    pub fn end_layer(&mut self) {
+        if matches!(self.render_state, RenderState::CameraRendering) {
+            set_camera(&self.camera);
+        }
+    }
+
+    // Draws into the inset until end_inset is called, must be called after set_camera. The scene
+    // needs drawing again inside, play and wait do this for the on_draw scene automatically.
+    // Layers aren't shown in insets.
+    pub fn begin_inset(&mut self, name: &str) -> Result<(), Error> {
+        if !matches!(self.render_state, RenderState::CameraRendering) {
+            return Err(Error::CameraNotSet);
+        }
+        let pixels_per_unit = self.render_target.texture.width() / self.world_size.x;
+        let camera = self.insets.camera(name, pixels_per_unit)?;
+
+        set_camera(&camera);
+        if self.transparent {
+            clear_background(Color::new(0., 0., 0., 0.));
+        } else {
+            clear_background(self.bg_color);
+        }
+        self.set_text_scale(&camera);
+        Ok(())
+    }
+
+    pub fn end_inset(&mut self) {
+        if matches!(self.render_state, RenderState::CameraRendering) {
+            set_camera(&self.camera);
+            self.set_text_scale(&self.camera);
+        }
     }
 
     pub fn set_default_camera(&mut self) {
         self.render_state = RenderState::ScreenRendering;
+        gl_use_default_material();
         set_default_camera();
+        TEXT_SCALE.with(|cell| cell.set(SCREEN_TEXT_SCALE));
     }
 
     pub fn draw_frame(&mut self) {
+        if let Err(err) = self.try_draw_frame() {
+            panic!("{err}");
+        }
+    }
+
+    // Like draw_frame but returns an error instead of panicking when the frame can't be drawn or
+    // exported
+    pub fn try_draw_frame(&mut self) -> Result<(), Error> {
         if matches!(self.render_state, RenderState::CameraRendering) {
-            panic!("Animation::set_default_camera must be called before you can draw the frame to the screen");
+            return Err(Error::RenderState);
         }
 
-        if let Some(material) = &self.fxaa_material {
-            material.set_uniform("texture_size", self.draw_size);
-            gl_use_material(material);
+        let background = (!self.transparent).then_some(self.bg_color);
+        let scene = if self.layers.is_empty() {
+            self.render_target.texture.clone()
         } else {
-            gl_use_default_material();
-        }
+            self.layers
+                .composite(&self.render_target.texture, background, self.filter_mode)
+        };
+        let scene = if self.insets.is_empty() {
+            scene
+        } else {
+            let matrix = self.camera.matrix();
+            let half_size = self.world_size / 2.;
+            self.insets
+                .draw_over(&scene, self.world_size, self.filter_mode, |point| {
+                    // The scene camera flips y, frame coordinates point up like the world
+                    let clip = matrix.transform_point3(point.extend(0.));
+                    vec2(clip.x, -clip.y) * half_size
+                })
+        };
+        let scene = match &mut self.supersampler {
+            Some(supersampler) => supersampler.resolve(&scene, self.filter_mode),
+            None => scene,
+        };
 
-        clear_background(self.bg_color);
+        // With motion blur every call renders one sub frame, the frame is only complete and
+        // exported after the last one
+        let mut frame_complete = true;
+        let scene = match (
+            self.export.as_ref().and_then(Export::shutter),
+            &mut self.accumulator,
+        ) {
+            (Some(motion_blur), Some(accumulator)) => {
+                if self.sub_frame == 0 {
+                    accumulator.reset();
+                }
+                accumulator.add(&scene, self.filter_mode);
+                self.sub_frame = (self.sub_frame + 1) % motion_blur.sub_frames();
+                frame_complete = self.sub_frame == 0;
+                accumulator.result(self.filter_mode).unwrap_or(scene)
+            }
+            _ => scene,
+        };
+        let frame = self
+            .post_pipeline
+            .apply(&scene, self.time(), self.filter_mode);
 
-        self.scale = Self::compute_scale(self.width, self.height);
+        self.letterbox.draw(self.bg_color);
 
-        self.draw_size = vec2(self.width * self.scale, self.height * self.scale);
+        let frame_rect = self.frame_rect();
         // Draw 'render_target' to window screen, porperly scaled and letterboxed
+        gl_use_material(&self.display_material);
         draw_texture_ex(
-            &self.render_target.texture,
-            (screen_width() - (self.width * self.scale)) * 0.5,
-            (screen_height() - (self.height * self.scale)) * 0.5,
+            &frame,
+            frame_rect.x,
+            frame_rect.y,
             WHITE,
             DrawTextureParams {
-                dest_size: Some(self.draw_size),
+                dest_size: Some(frame_rect.size()),
                 ..Default::default()
             },
         );
-
         gl_use_default_material();
+
+        if let Some(export) = self.export.as_mut().filter(|_| frame_complete) {
+            export.write_frame(&frame, background)?;
+
+            if export.is_finished() {
+                self.stop_export()?;
+            }
+        }
+
+        if let Some(playback) = &self.playback {
+            playback.draw_timeline(&self.clock);
+        }
+
+        Ok(())
     }
 
+    // Window pixels per frame pixel horizontally, the vertical scale only differs when stretching
     pub fn scale(&self) -> f32 {
-        self.scale
+        self.frame_rect().w / self.width
     }
 
-    pub fn size(&self) -> Vec2 {
-        vec2(self.width, self.height)
+    pub fn bg_color(&self) -> Color {
+        self.bg_color
     }
 
-    fn compute_scale(width: f32, height: f32) -> f32 {
-        f32::min(screen_width() / width, screen_height() / height)
+    // Clears the render target to transparent instead of the background colour. Exports keep the
+    // alpha channel while the window still shows the frame over the background colour.
+    pub fn set_transparent(&mut self, transparent: bool) {
+        self.transparent = transparent;
     }
 
-    fn resize_render_target(&mut self, new_width: f32, new_height: f32) {
-        self.width = new_width;
-        self.height = new_height;
-        let render_target = render_target(new_width as u32, new_height as u32);
-        render_target.texture.set_filter(self.filter_mode);
-
-        let mut camera = Camera2D::from_display_rect(Rect::new(0., 0., new_width, new_height));
+    pub fn is_transparent(&self) -> bool {
+        self.transparent
+    }
 
-        camera.render_target = Some(render_target.clone());
-        camera.target = vec2(0., 0.);
+    // The world size covered by the frame at a camera zoom of 1
+    pub fn size(&self) -> Vec2 {
+        self.world_size
+    }
 
-        self.camera = camera;
-        self.render_target = render_target;
+    // The size of the frame in logical pixels, the size the animation was created with
+    pub fn logical_size(&self) -> Vec2 {
+        vec2(self.width, self.height)
     }
-}
 
-pub fn map(val: f32, min1: f32, max1: f32, min2: f32, max2: f32) -> f32 {
-    ((val - min1) / (max1 - min1)) * (max2 - min2) + min2
-}
+    // e.g. vec2(16., 9.) for a frame 16 units wide, the world keeps the origin at the centre.
+    // Defaults to the logical size, one unit per logical pixel. Units are square, so the world
+    // size must have the aspect ratio of the logical size. Style defaults are in world units, the
+    // styles' for_animation constructors size them in logical pixels of the current world size.
+    pub fn set_world_size(&mut self, world_size: Vec2) -> Result<(), Error> {
+        let aspect_ratio = world_size.x / world_size.y;
+        if !(world_size.x > 0. && world_size.y > 0. && world_size.is_finite())
+            || (aspect_ratio / (self.width / self.height) - 1.).abs() > WORLD_ASPECT_EPSILON
+        {
+            return Err(Error::InvalidWorldSize {
+                width: world_size.x,
+                height: world_size.y,
+            });
+        }
 
-const FXAA_VERTEX_SHADER: &str = r#"#version 100
-attribute vec3 position;
-attribute vec2 texcoord;
+        self.world_size = world_size;
+        self.apply_view(self.view);
+        Ok(())
+    }
 
-varying lowp vec2 uv;
+    // Sets the world width and picks the height that keeps the aspect ratio of the frame
+    pub fn set_world_width(&mut self, world_width: f32) -> Result<(), Error> {
+        self.set_world_size(vec2(world_width, world_width * self.height / self.width))
+    }
 
-uniform mat4 Model;
-uniform mat4 Projection;
+    // World units per logical pixel at a camera zoom of 1, the same along x and y. Zooming scales everything drawn in
+    // world units, including thicknesses and text converted with these helpers.
+    pub fn units_per_pixel(&self) -> f32 {
+        self.world_size.x / self.width
+    }
 
-void main() {
-    gl_Position = Projection * Model * vec4(position, 1);
-    uv = texcoord;
-}"#;
-const FXAA_FRAGMENT_SHADER: &str = r#"
-#version 100
-precision lowp float;
+    // e.g. a line thickness of pixels_to_units(2.) is 2 pixels wide in a frame of the logical size
+    pub fn pixels_to_units(&self, pixels: f32) -> f32 {
+        pixels * self.units_per_pixel()
+    }
 
-// THIS CODE IS THANKS TO: 
-// https://blog.simonrodriguez.fr/articles/2016/07/implementing_fxaa.html
+    // Font sizes are in logical pixels so units_to_pixels(0.5) gives text half a unit tall
+    pub fn units_to_pixels(&self, units: f32) -> f32 {
+        units / self.units_per_pixel()
+    }
 
-varying vec2 uv;
+    // The scene drawn every frame by play and wait. It's called in world space after set_camera.
+    pub fn on_draw(&mut self, draw_scene: impl FnMut(&Animation) + 'static) {
+        self.draw_scene = Some(Box::new(draw_scene));
+    }
 
-// UNIFORMS
-uniform sampler2D Texture;
-uniform vec2 texture_size;
+    pub async fn play(&mut self, mut action: impl Action, duration: f32) {
+        action.begin();
+        self.run_for(duration, |_, progress| action.update(progress))
+            .await;
+    }
 
-// CONSTANTS
-const float EDGE_THRESHOLD_MIN = 0.0312;
-const float EDGE_THRESHOLD_MAX = 0.125;
-const int ITERATIONS = 12;
-const float SUBPIXEL_QUALITY = 0.75;
+    pub async fn wait(&mut self, duration: f32) {
+        self.run_for(duration, |_, _| ()).await;
+    }
 
-float rgb2luma(vec3 rgb){
-    return sqrt(dot(rgb, vec3(0.299, 0.587, 0.114)));
-}
+    // Moves the camera from its current view to the given view while playing the scene. Fails
+    // before playing for the same views set_view rejects.
+    pub async fn animate_view(
+        &mut self,
+        to: CameraView,
+        duration: f32,
+        easing: Easing,
+    ) -> Result<(), Error> {
+        to.validate()?;
+        let from = self.view;
+        self.run_for(duration, |animation, progress| {
+            animation.apply_view(from.lerp(&to, easing.ease(progress)))
+        })
+        .await;
+        Ok(())
+    }
 
-float get_quality(int i) {
-    float quality = 0.0;
-    if(i < 6) {
-       quality = 1.0; 
-    } else if(i == 6) {
-       quality = 1.5; 
-    } else if(i > 6 && i < 10) {
-       quality = 2.0; 
-    } else if(i == 10) {
-       quality = 4.0; 
-    } else {
-        quality = 8.0;
-    }
-
-    return quality;
-}
+    // Renders the scene every frame for the duration, calling update with the progress
+    // through it before the camera is set. The first frame shows progress 0 and the last 1.
+    async fn run_for(&mut self, duration: f32, mut update: impl FnMut(&mut Self, f32)) {
+        let mut draw_scene = self.draw_scene.take();
+        let result = self
+            .run_frames(
+                &mut draw_scene,
+                |_, animation, elapsed| {
+                    let progress = sequence::progress(elapsed, duration);
+                    update(animation, progress);
+                    progress < 1.
+                },
+                |draw_scene, animation| {
+                    if let Some(draw_scene) = draw_scene {
+                        draw_scene(animation);
+                    }
+                },
+            )
+            .await;
+        self.draw_scene = draw_scene;
+
+        if let Err(err) = result {
+            panic!("{err}");
+        }
+    }
 
-void main() {
-    vec2 inverseScreenSize = vec2(1.0/texture_size.x, 1.0/texture_size.y);
-    vec3 colorCenter = texture2D(Texture,uv).rgb;
-
-    // Luma at the current fragment
-    float lumaCenter = rgb2luma(colorCenter);
-
-    // Directions
-    vec2 up = vec2(0., -inverseScreenSize.y);
-    vec2 down = vec2(0., inverseScreenSize.y);
-    vec2 left = vec2(-inverseScreenSize.x, 0.);
-    vec2 right = vec2(inverseScreenSize.x, 0.);
-
-    // Luma at the four direct neighbours of the current fragment.
-    float lumaDown = rgb2luma(texture2D(Texture, uv + down).rgb);
-    float lumaUp = rgb2luma(texture2D(Texture,uv + up).rgb);
-    float lumaLeft = rgb2luma(texture2D(Texture,uv + left).rgb);
-    float lumaRight = rgb2luma(texture2D(Texture,uv + right).rgb);
+    // Renders frames until update returns false, calling it before the camera is set with the
+    // time since the first frame, which is 0. draw is called in world space for the frame and
+    // again for every inset.
+    pub(crate) async fn run_frames<S: ?Sized>(
+        &mut self,
+        state: &mut S,
+        mut update: impl FnMut(&mut S, &mut Self, f32) -> bool,
+        mut draw: impl FnMut(&mut S, &Self),
+    ) -> Result<(), Error> {
+        let mut start = None;
+        loop {
+            self.tick_clock();
+            let time = self.time();
+            let elapsed = time - *start.get_or_insert(time);
+            let running = update(state, self, elapsed);
+            self.bind_camera();
+
+            draw(state, self);
+            for name in self.insets.names() {
+                if self.begin_inset(&name).is_ok() {
+                    draw(state, self);
+                    self.end_inset();
+                }
+            }
 
-    // Find the maximum and minimum luma around the current fragment.
-    float lumaMin = min(lumaCenter,min(min(lumaDown,lumaUp),min(lumaLeft,lumaRight)));
-    float lumaMax = max(lumaCenter,max(max(lumaDown,lumaUp),max(lumaLeft,lumaRight)));
-
-    // Compute the delta.
-    float lumaRange = lumaMax - lumaMin;
-
-    // If the luma variation is lower that a threshold (or if we are in a really dark area), we are not on an edge, don't perform any AA.
-    if(lumaRange < max(EDGE_THRESHOLD_MIN,lumaMax*EDGE_THRESHOLD_MAX)){
-        gl_FragColor = vec4(colorCenter, 1.0);
-        return;
-    }
-
-    // Query the 4 remaining corners lumas.
-    float lumaDownLeft = rgb2luma(texture2D(Texture,uv + down + left).rgb);
-    float lumaUpRight = rgb2luma(texture2D(Texture,uv + up + right).rgb);
-    float lumaUpLeft = rgb2luma(texture2D(Texture,uv + up + left).rgb);
-    float lumaDownRight = rgb2luma(texture2D(Texture,uv + down + right).rgb);
-
-    // Combine the four edges lumas (using intermediary variables for future computations with the same values).
-    float lumaDownUp = lumaDown + lumaUp;
-    float lumaLeftRight = lumaLeft + lumaRight;
-
-    // Same for corners
-    float lumaLeftCorners = lumaDownLeft + lumaUpLeft;
-    float lumaDownCorners = lumaDownLeft + lumaDownRight;
-    float lumaRightCorners = lumaDownRight + lumaUpRight;
-    float lumaUpCorners = lumaUpRight + lumaUpLeft;
-
-    // Compute an estimation of the gradient along the horizontal and vertical axis.
-    float edgeHorizontal =  abs(-2.0 * lumaLeft + lumaLeftCorners)  + abs(-2.0 * lumaCenter + lumaDownUp ) * 2.0    + abs(-2.0 * lumaRight + lumaRightCorners);
-    float edgeVertical =    abs(-2.0 * lumaUp + lumaUpCorners)      + abs(-2.0 * lumaCenter + lumaLeftRight) * 2.0  + abs(-2.0 * lumaDown + lumaDownCorners);
-
-    // Is the local edge horizontal or vertical ?
-    bool isHorizontal = (edgeHorizontal >= edgeVertical);
-
-    // Select the two neighboring texels lumas in the opposite direction to the local edge.
-    float luma1 = isHorizontal ? lumaDown : lumaLeft;
-    float luma2 = isHorizontal ? lumaUp : lumaRight;
-    // Compute gradients in this direction.
-    float gradient1 = luma1 - lumaCenter;
-    float gradient2 = luma2 - lumaCenter;
-
-    // Which direction is the steepest ?
-    bool is1Steepest = abs(gradient1) >= abs(gradient2);
-
-    // Gradient in the corresponding direction, normalized.
-    float gradientScaled = 0.25*max(abs(gradient1),abs(gradient2));
-
-    // Choose the step size (one pixel) according to the edge direction.
-    float stepLength = isHorizontal ? inverseScreenSize.y : inverseScreenSize.x;
-
-    // Average luma in the correct direction.
-    float lumaLocalAverage = 0.0;
-
-    if(is1Steepest){
-        // Switch the direction
-        stepLength = - stepLength;
-        lumaLocalAverage = 0.5*(luma1 + lumaCenter);
-    } else {
-        lumaLocalAverage = 0.5*(luma2 + lumaCenter);
-    }
-
-    // Shift UV in the correct direction by half a pixel.
-    vec2 currentUv = uv;
-    if(isHorizontal){
-        currentUv.y += stepLength * 0.5;
-    } else {
-        currentUv.x += stepLength * 0.5;
-    }
-
-    // Compute offset (for each iteration step) in the right direction.
-    vec2 offset = isHorizontal ? vec2(inverseScreenSize.x,0.0) : vec2(0.0,inverseScreenSize.y);
-    // Compute UVs to explore on each side of the edge, orthogonally. The QUALITY allows us to step faster.
-    vec2 uv1 = currentUv - offset;
-    vec2 uv2 = currentUv + offset;
-
-    // Read the lumas at both current extremities of the exploration segment, and compute the delta wrt to the local average luma.
-    float lumaEnd1 = rgb2luma(texture2D(Texture,uv1).rgb);
-    float lumaEnd2 = rgb2luma(texture2D(Texture,uv2).rgb);
-    lumaEnd1 -= lumaLocalAverage;
-    lumaEnd2 -= lumaLocalAverage;
-
-    // If the luma deltas at the current extremities are larger than the local gradient, we have reached the side of the edge.
-    bool reached1 = abs(lumaEnd1) >= gradientScaled;
-    bool reached2 = abs(lumaEnd2) >= gradientScaled;
-    bool reachedBoth = reached1 && reached2;
-
-    // If the side is not reached, we continue to explore in this direction.
-    if(!reached1){
-        uv1 -= offset;
-    }
-    if(!reached2){
-        uv2 += offset;
-    }
-
-    // If both sides have not been reached, continue to explore.
-    if(!reachedBoth){
+            self.set_default_camera();
+            self.try_draw_frame()?;
+            next_frame().await;
 
-        for(int i = 2; i < ITERATIONS; i++){
-            // If needed, read luma in 1st direction, compute delta.
-            if(!reached1){
-                lumaEnd1 = rgb2luma(texture2D(Texture, uv1).rgb);
-                lumaEnd1 = lumaEnd1 - lumaLocalAverage;
+            if !running {
+                return Ok(());
             }
-            // If needed, read luma in opposite direction, compute delta.
-            if(!reached2){
-                lumaEnd2 = rgb2luma(texture2D(Texture, uv2).rgb);
-                lumaEnd2 = lumaEnd2 - lumaLocalAverage;
-            }
-            // If the luma deltas at the current extremities is larger than the local gradient, we have reached the side of the edge.
-            reached1 = abs(lumaEnd1) >= gradientScaled;
-            reached2 = abs(lumaEnd2) >= gradientScaled;
-            reachedBoth = reached1 && reached2;
-
-            // If the side is not reached, we continue to explore in this direction, with a variable quality.
-            if(!reached1){
-                uv1 -= offset * get_quality(i);
-            }
-            if(!reached2){
-                uv2 += offset * get_quality(i);
-            }
-
-            // If both sides have been reached, stop the exploration.
-            if(reachedBoth){ break;}
         }
     }
 
-    // Compute the distances to each extremity of the edge.
-    float distance1 = isHorizontal ? (uv.x - uv1.x) : (uv.y - uv1.y);
-    float distance2 = isHorizontal ? (uv2.x - uv.x) : (uv2.y - uv.y);
-
-    // which direction is the extremity of the edge closer ?
-    bool isDirection1 = distance1 < distance2;
-    float distanceFinal = min(distance1, distance2);
-
-    // Length of the edge.
-    float edgeThickness = (distance1 + distance2);
-
-    // UV offset: read in the direction of the closest side of the edge.
-    float pixelOffset = - distanceFinal / edgeThickness + 0.5;
-
-    // Is the luma at center smaller than the local average ?
-    bool isLumaCenterSmaller = lumaCenter < lumaLocalAverage;
-
-    // If the luma at center is smaller than at its neighbour, the delta luma at each end should be positive (same variation).
-    // (in the direction of the closer side of the edge.)
-    bool correctVariation = ((isDirection1 ? lumaEnd1 : lumaEnd2) < 0.0) != isLumaCenterSmaller;
+    fn ssaa_factor(&self) -> u32 {
+        self.supersampler
+            .as_ref()
+            .map_or(1, |supersampler| supersampler.factor())
+    }
 
-    // If the luma variation is incorrect, do not offset.
-    float finalOffset = correctVariation ? pixelOffset : 0.0;
+    // Recreates the render target at the current render size, the camera keeps covering the
+    // logical size so world coordinates don't move
+    fn resize_render_target(&mut self) {
+        let size = self.render_size() * self.ssaa_factor();
+        let render_target = render_target(size.x, size.y);
+        render_target.texture.set_filter(self.filter_mode);
 
-    // Sub-pixel shifting
-    // Full weighted average of the luma over the 3x3 neighborhood.
-    float lumaAverage = (1.0/12.0) * (2.0 * (lumaDownUp + lumaLeftRight) + lumaLeftCorners + lumaRightCorners);
-    // Ratio of the delta between the global average and the center luma, over the luma range in the 3x3 neighborhood.
-    float subPixelOffset1 = clamp(abs(lumaAverage - lumaCenter)/lumaRange,0.0,1.0);
-    float subPixelOffset2 = (-2.0 * subPixelOffset1 + 3.0) * subPixelOffset1 * subPixelOffset1;
-    // Compute a sub-pixel offset based on this delta.
-    float subPixelOffsetFinal = subPixelOffset2 * subPixelOffset2 * SUBPIXEL_QUALITY;
+        let mut camera =
+            Camera2D::from_display_rect(Rect::new(0., 0., self.world_size.x, self.world_size.y));
 
-    // Pick the biggest of the two offsets.
-    finalOffset = max(finalOffset,subPixelOffsetFinal);
+        camera.render_target = Some(render_target.clone());
+        self.view.apply(&mut camera, self.size());
 
-    // Compute the final UV coordinates.
-    vec2 finalUv = uv;
-    if(isHorizontal){
-        finalUv.y += finalOffset * stepLength;
-    } else {
-        finalUv.x += finalOffset * stepLength;
+        self.camera = camera;
+        // Draws queued this frame may still use the old target
+        let old = std::mem::replace(&mut self.render_target, render_target);
+        self.retired_targets.push(old);
+        self.layers.resize(size, self.filter_mode);
     }
+}
 
-    // Read the color at the new UV coordinates, and use it.
-    vec3 finalColor = texture2D(Texture,finalUv).rgb;
-
-    gl_FragColor = vec4(finalColor, 1.0);
-}"#;
+pub fn map(val: f32, min1: f32, max1: f32, min2: f32, max2: f32) -> f32 {
+    ((val - min1) / (max1 - min1)) * (max2 - min2) + min2
+}
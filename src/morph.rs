@@ -0,0 +1,382 @@
+use macroquad::prelude::*;
+
+use crate::{
+    easing::Easing,
+    path::{Path, Polyline, Segment},
+    sequence::{Action, Property},
+    shapes::{signed_area, Shape, ShapeStyle},
+    tween::Lerp,
+};
+
+// Fewest points a pair of subpaths is resampled to, so simple outlines still bend smoothly
+const MIN_MORPH_POINTS: usize = 64;
+
+// A pair of subpaths resampled to the same number of points, matched point by point
+struct MorphPair {
+    from: Vec<Vec2>,
+    to: Vec<Vec2>,
+    closed: bool,
+}
+
+// Turns one path into another. Subpaths are paired with the subpath of the other path nearest in
+// position and size, so the holes of glyphs turn into holes. Both outlines of a pair are resampled
+// by arc length to the same number of points, closed outlines are turned to wind the same way and
+// start from the closest points. Subpaths left without a pair grow from or shrink into their
+// centres. Building a morph does the matching once, path_at is cheap to call every frame.
+pub struct Morph {
+    pairs: Vec<MorphPair>,
+    from_style: ShapeStyle,
+    to_style: ShapeStyle,
+}
+
+impl Morph {
+    pub fn new(from: &Path, to: &Path) -> Self {
+        let from_polylines = from.polylines();
+        let to_polylines = to.polylines();
+
+        let pairs = pair_subpaths(&from_polylines, &to_polylines)
+            .into_iter()
+            .map(|pair| match pair {
+                (Some(from), Some(to)) => match_pair(&from_polylines[from], &to_polylines[to]),
+                (Some(from), None) => collapse(&from_polylines[from], true),
+                (None, Some(to)) => collapse(&to_polylines[to], false),
+                (None, None) => unreachable!(),
+            })
+            .collect();
+
+        Self {
+            pairs,
+            from_style: from.style().clone(),
+            to_style: to.style().clone(),
+        }
+    }
+
+    // The path part of the way through the morph, in world coordinates
+    pub fn path_at(&self, t: f32) -> Path {
+        let mut path = Path::new();
+        for pair in &self.pairs {
+            for (index, (from, to)) in pair.from.iter().zip(&pair.to).enumerate() {
+                let point = from.lerp(to, t);
+                path.push(if index == 0 {
+                    Segment::MoveTo(point)
+                } else {
+                    Segment::LineTo(point)
+                });
+            }
+            if pair.closed {
+                path.push(Segment::Close);
+            }
+        }
+
+        *path.style_mut() = self.from_style.lerp(&self.to_style, t);
+        path
+    }
+
+    pub fn draw(&self, t: f32) {
+        self.path_at(t).draw();
+    }
+}
+
+// Every lerp builds a new morph, tween_to and morph_to build one for the whole tween
+impl Lerp for Path {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Morph::new(self, other).path_at(t)
+    }
+
+    fn lerper(&self, other: &Self) -> Box<dyn Fn(f32) -> Self> {
+        let morph = Morph::new(self, other);
+        Box::new(move |t| morph.path_at(t))
+    }
+}
+
+// Pairs subpaths by how far apart their centres are and how different their sizes are, closest
+// first. Closed subpaths prefer closed ones. Unpaired subpaths come last.
+fn pair_subpaths(from: &[Polyline], to: &[Polyline]) -> Vec<(Option<usize>, Option<usize>)> {
+    let features = |polyline: &Polyline| {
+        let min = polyline.points.iter().copied().reduce(Vec2::min);
+        let max = polyline.points.iter().copied().reduce(Vec2::max);
+        let center = min
+            .zip(max)
+            .map_or(Vec2::ZERO, |(min, max)| (min + max) / 2.);
+        let size = if polyline.closed {
+            signed_area(&polyline.points).abs().sqrt()
+        } else {
+            polyline.length()
+        };
+        (center, size, polyline.closed)
+    };
+    let from_features: Vec<_> = from.iter().map(features).collect();
+    let to_features: Vec<_> = to.iter().map(features).collect();
+
+    let mut candidates: Vec<(bool, f32, usize, usize)> = Vec::new();
+    for (from_index, (from_center, from_size, from_closed)) in from_features.iter().enumerate() {
+        for (to_index, (to_center, to_size, to_closed)) in to_features.iter().enumerate() {
+            let cost = from_center.distance_squared(*to_center) + (from_size - to_size).powi(2);
+            candidates.push((from_closed != to_closed, cost, from_index, to_index));
+        }
+    }
+    candidates.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)));
+
+    let mut from_pairs = vec![None; from.len()];
+    let mut to_paired = vec![false; to.len()];
+    for (_, _, from_index, to_index) in candidates {
+        if from_pairs[from_index].is_none() && !to_paired[to_index] {
+            from_pairs[from_index] = Some(to_index);
+            to_paired[to_index] = true;
+        }
+    }
+
+    let mut pairs: Vec<(Option<usize>, Option<usize>)> = from_pairs
+        .iter()
+        .enumerate()
+        .map(|(from_index, to_index)| (Some(from_index), *to_index))
+        .collect();
+    pairs.extend(
+        (0..to.len())
+            .filter(|&to_index| !to_paired[to_index])
+            .map(|to_index| (None, Some(to_index))),
+    );
+    pairs
+}
+
+fn match_pair(from: &Polyline, to: &Polyline) -> MorphPair {
+    let count = from.points.len().max(to.points.len()).max(MIN_MORPH_POINTS);
+    // A closed outline turning into an open one is cut open at its start
+    let closed = from.closed && to.closed;
+    let from_points = resample(from, count, closed);
+    let mut to_points = resample(to, count, closed);
+
+    if closed {
+        if (signed_area(&from_points) > 0.) != (signed_area(&to_points) > 0.) {
+            to_points.reverse();
+        }
+        // Starts from the rotation that moves the points the least
+        let best = (0..count)
+            .map(|rotation| (rotation, rotation_cost(&from_points, &to_points, rotation)))
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map_or(0, |(rotation, _)| rotation);
+        to_points.rotate_left(best);
+    } else {
+        let ends = |to_start: Vec2, to_end: Vec2| {
+            from_points[0].distance(to_start) + from_points[count - 1].distance(to_end)
+        };
+        if ends(to_points[count - 1], to_points[0]) < ends(to_points[0], to_points[count - 1]) {
+            to_points.reverse();
+        }
+    }
+
+    MorphPair {
+        from: from_points,
+        to: to_points,
+        closed,
+    }
+}
+
+fn rotation_cost(from: &[Vec2], to: &[Vec2], rotation: usize) -> f32 {
+    from.iter()
+        .enumerate()
+        .map(|(index, point)| point.distance_squared(to[(index + rotation) % to.len()]))
+        .sum()
+}
+
+// A subpath with nothing to turn into shrinks to its centre, or grows from it
+fn collapse(polyline: &Polyline, shrinking: bool) -> MorphPair {
+    let count = polyline.points.len().max(MIN_MORPH_POINTS);
+    let points = resample(polyline, count, polyline.closed);
+    let center = points.iter().sum::<Vec2>() / count as f32;
+    let collapsed = vec![center; count];
+
+    let (from, to) = if shrinking {
+        (points, collapsed)
+    } else {
+        (collapsed, points)
+    };
+    MorphPair {
+        from,
+        to,
+        closed: polyline.closed,
+    }
+}
+
+// Points evenly spaced along the polyline. A closed polyline resampled as closed leaves out the
+// point that would land back on its start, resampled as open it is cut open at its start.
+fn resample(polyline: &Polyline, count: usize, closed: bool) -> Vec<Vec2> {
+    let mut points = polyline.points.clone();
+    if polyline.closed {
+        points.extend(polyline.points.first());
+    }
+    let lengths: Vec<f32> = points
+        .windows(2)
+        .scan(0., |total, pair| {
+            *total += pair[0].distance(pair[1]);
+            Some(*total)
+        })
+        .collect();
+    let total = lengths.last().copied().unwrap_or(0.);
+    if total <= 0. {
+        return vec![points.first().copied().unwrap_or_default(); count];
+    }
+
+    let spacing = if closed {
+        total / count as f32
+    } else {
+        total / (count - 1).max(1) as f32
+    };
+    let mut segment = 0;
+    (0..count)
+        .map(|index| {
+            let distance = (index as f32 * spacing).min(total);
+            while segment + 1 < lengths.len() && lengths[segment] < distance {
+                segment += 1;
+            }
+            let start = if segment == 0 {
+                0.
+            } else {
+                lengths[segment - 1]
+            };
+            let length = lengths[segment] - start;
+            let t = if length > 0. {
+                (distance - start) / length
+            } else {
+                0.
+            };
+            points[segment].lerp(points[segment + 1], t)
+        })
+        .collect()
+}
+
+// Morphs a path property from whatever it is when the action begins into another path, ending on
+// exactly the target path
+pub struct MorphTo {
+    property: Property<Path>,
+    morph: Option<Morph>,
+    to: Path,
+    easing: Easing,
+}
+
+impl MorphTo {
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+}
+
+impl Action for MorphTo {
+    fn begin(&mut self) {
+        self.morph = Some(Morph::new(&self.property.borrow(), &self.to));
+    }
+
+    fn update(&mut self, progress: f32) {
+        if progress >= 1. {
+            self.property.set(self.to.clone());
+            return;
+        }
+
+        let morph = self
+            .morph
+            .get_or_insert_with(|| Morph::new(&self.property.borrow(), &self.to));
+        self.property.set(morph.path_at(self.easing.ease(progress)));
+    }
+}
+
+pub fn morph_to(property: &Property<Path>, to: Path) -> MorphTo {
+    MorphTo {
+        property: property.clone(),
+        morph: None,
+        to,
+        easing: Easing::SmoothStep,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(center: Vec2, size: f32) -> Path {
+        let half = size / 2.;
+        Path::new()
+            .move_to(center + vec2(-half, -half))
+            .line_to(center + vec2(half, -half))
+            .line_to(center + vec2(half, half))
+            .line_to(center + vec2(-half, half))
+            .close()
+    }
+
+    fn with(path: &Path, other: &Path) -> Path {
+        let mut path = path.clone();
+        for segment in other.segments() {
+            path.push(*segment);
+        }
+        path
+    }
+
+    #[test]
+    fn resamples_evenly_by_length() {
+        let polyline = Polyline {
+            points: vec![vec2(0., 0.), vec2(1., 0.), vec2(4., 0.)],
+            closed: false,
+        };
+        let points = resample(&polyline, 5, false);
+        assert_eq!(
+            points,
+            vec![
+                vec2(0., 0.),
+                vec2(1., 0.),
+                vec2(2., 0.),
+                vec2(3., 0.),
+                vec2(4., 0.)
+            ]
+        );
+
+        // Closed outlines leave out the point back at the start
+        let square = square(vec2(0.5, 0.5), 1.).polylines().remove(0);
+        let points = resample(&square, 8, true);
+        assert_eq!(points.len(), 8);
+        assert_eq!(points[1], vec2(0.5, 0.));
+        assert_eq!(points[7], vec2(0., 0.5));
+    }
+
+    #[test]
+    fn pairs_subpaths_by_position_and_size() {
+        let big = square(vec2(0., 0.), 10.);
+        let small = square(vec2(20., 0.), 2.);
+        let hole = square(vec2(0., 0.), 4.);
+        let from = with(&big, &small).polylines();
+        let to = with(&with(&small, &hole), &big).polylines();
+        assert_eq!(
+            pair_subpaths(&from, &to),
+            vec![(Some(0), Some(2)), (Some(1), Some(0)), (None, Some(1))]
+        );
+    }
+
+    #[test]
+    fn morphs_end_on_the_target() {
+        let from = square(vec2(0., 0.), 2.);
+        let to = with(&square(vec2(10., 0.), 4.), &square(vec2(10., 0.), 1.));
+        let morph = Morph::new(&from, &to);
+
+        let area = |path: &Path| -> Vec<f32> {
+            path.polylines()
+                .iter()
+                .map(|polyline| signed_area(&polyline.points).abs())
+                .collect()
+        };
+        let start = morph.path_at(0.);
+        assert_eq!(start.polylines().len(), 2);
+        assert!((area(&start)[0] - 4.).abs() < 1e-3);
+        assert!(area(&start)[1] < 1e-6);
+        // The unit square is closer in size, the big one grows from its centre
+        let end = area(&morph.path_at(1.));
+        assert!((end[0] - 1.).abs() < 1e-3 && (end[1] - 16.).abs() < 1e-3);
+    }
+
+    #[test]
+    fn lerpers_match_lerp() {
+        let from = square(vec2(0., 0.), 2.);
+        let to = with(&square(vec2(5., 5.), 3.), &square(vec2(5., 5.), 1.));
+        let lerper = from.lerper(&to);
+        for t in [0., 0.25, 0.5, 1.] {
+            assert_eq!(lerper(t), from.lerp(&to, t));
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use macroquad::{miniquad, prelude::*};
+
+use crate::{blend::premultiplied_material, error::Error, post::POST_VERTEX_SHADER};
+
+#[derive(Copy, Clone, Debug)]
+pub struct MotionBlur {
+    sub_frames: u32,
+    shutter_angle: f32,
+}
+
+impl MotionBlur {
+    // Averages sub_frames frames for every exported frame, rendered while the shutter is open. The
+    // shutter angle is how much of the time between frames the shutter is open in degrees, 360
+    // blurs over the whole frame and 180 is the usual film look.
+    pub fn new(sub_frames: u32, shutter_angle: f32) -> Result<Self, Error> {
+        if sub_frames == 0 {
+            return Err(Error::InvalidSubFrames(sub_frames));
+        }
+        if !(0. ..=360.).contains(&shutter_angle) {
+            return Err(Error::InvalidShutterAngle(shutter_angle));
+        }
+        Ok(Self {
+            sub_frames,
+            shutter_angle,
+        })
+    }
+
+    pub fn sub_frames(&self) -> u32 {
+        self.sub_frames
+    }
+
+    pub fn shutter_angle(&self) -> f32 {
+        self.shutter_angle
+    }
+
+    // How far into the step between frames the given sub frame is rendered
+    pub(crate) fn sub_frame_fraction(&self, sub_frame: u32) -> f32 {
+        sub_frame as f32 / self.sub_frames as f32 * self.shutter_angle / 360.
+    }
+}
+
+// Keeps a running average of the frames added since the last reset. Averaging instead of summing
+// pre divided frames means parts of the scene that don't move come out unchanged. The average is
+// kept in half float targets where the GPU can render to them so rounding doesn't build up over the
+// sub frames, and copied into an 8 bit target for display and export.
+pub(crate) struct Accumulator {
+    material: Material,
+    copy_material: Material,
+    targets: Vec<RenderTarget>,
+    output: Option<RenderTarget>,
+    current: usize,
+    count: u32,
+}
+
+impl Accumulator {
+    pub(crate) fn new() -> Result<Self, Error> {
+        let material = load_material(
+            ShaderSource::Glsl {
+                vertex: POST_VERTEX_SHADER,
+                fragment: ACCUMULATE_FRAGMENT_SHADER,
+            },
+            MaterialParams {
+                uniforms: vec![("weight".to_string(), UniformType::Float1)],
+                textures: vec!["Accumulated".to_string()],
+                ..Default::default()
+            },
+        )
+        .map_err(|source| Error::Shader {
+            name: "motion blur".to_owned(),
+            source,
+        })?;
+
+        Ok(Self {
+            material,
+            copy_material: premultiplied_material()?,
+            targets: Vec::new(),
+            output: None,
+            current: 0,
+            count: 0,
+        })
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.count = 0;
+    }
+
+    pub(crate) fn add(&mut self, source: &Texture2D, filter_mode: FilterMode) {
+        let size = source.size();
+        if self
+            .targets
+            .first()
+            .is_none_or(|target| target.texture.size() != size)
+        {
+            for target in &self.targets {
+                target.delete();
+            }
+            let float = supports_float_targets();
+            self.targets = (0..2)
+                .map(|_| {
+                    if float {
+                        float_render_target(size.x as u32, size.y as u32)
+                    } else {
+                        render_target(size.x as u32, size.y as u32)
+                    }
+                })
+                .collect();
+            self.count = 0;
+        }
+
+        let next = 1 - self.current;
+        let target = &self.targets[next];
+        target.texture.set_filter(filter_mode);
+
+        // Keeps the first row at the top, the same orientation as the source
+        set_camera(&Camera2D {
+            target: size / 2.,
+            zoom: vec2(2. / size.x, 2. / size.y),
+            render_target: Some(target.clone()),
+            ..Default::default()
+        });
+
+        // The first frame has a weight of 1 so whatever was accumulated before is ignored
+        self.material
+            .set_uniform("weight", 1. / (self.count + 1) as f32);
+        self.material
+            .set_texture("Accumulated", self.targets[self.current].texture.clone());
+        gl_use_material(&self.material);
+        draw_texture_ex(
+            source,
+            0.,
+            0.,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(size),
+                ..Default::default()
+            },
+        );
+        gl_use_default_material();
+        set_default_camera();
+
+        self.current = next;
+        self.count += 1;
+    }
+
+    pub(crate) fn result(&mut self, filter_mode: FilterMode) -> Option<Texture2D> {
+        let accumulated = self.targets.get(self.current)?;
+        let size = accumulated.texture.size();
+        if let Some(output) = self.output.take_if(|output| output.texture.size() != size) {
+            output.delete();
+        }
+        let output = self
+            .output
+            .get_or_insert_with(|| render_target(size.x as u32, size.y as u32));
+        output.texture.set_filter(filter_mode);
+
+        set_camera(&Camera2D {
+            target: size / 2.,
+            zoom: vec2(2. / size.x, 2. / size.y),
+            render_target: Some(output.clone()),
+            ..Default::default()
+        });
+        clear_background(Color::new(0., 0., 0., 0.));
+        gl_use_material(&self.copy_material);
+        draw_texture_ex(
+            &accumulated.texture,
+            0.,
+            0.,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(size),
+                ..Default::default()
+            },
+        );
+        gl_use_default_material();
+        set_default_camera();
+
+        Some(output.texture.clone())
+    }
+}
+
+// Rendering to half floats is core from OpenGL 3 and on Metal. OpenGL ES and WebGL only have it
+// as an extension, they get 8 bit targets.
+fn supports_float_targets() -> bool {
+    let info = unsafe { get_internal_gl() }.quad_context.info();
+    info.backend == miniquad::Backend::Metal || info.glsl_support.v130 || info.glsl_support.v330
+}
+
+// macroquad only makes 8 bit render targets
+fn float_render_target(width: u32, height: u32) -> RenderTarget {
+    let context = unsafe { get_internal_gl() }.quad_context;
+    let texture = context.new_render_texture(miniquad::TextureParams {
+        width,
+        height,
+        format: miniquad::TextureFormat::RGBA16F,
+        ..Default::default()
+    });
+    let render_pass = context.new_render_pass(texture, None);
+    RenderTarget {
+        texture: Texture2D::from_miniquad_texture(texture),
+        render_pass,
+    }
+}
+
+const ACCUMULATE_FRAGMENT_SHADER: &str = r#"#version 100
+precision highp float;
+
+varying vec2 uv;
+
+uniform sampler2D Texture;
+uniform sampler2D Accumulated;
+uniform float weight;
+
+void main() {
+    gl_FragColor = mix(texture2D(Accumulated, uv), texture2D(Texture, uv), weight);
+}"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_invalid_settings() {
+        assert!(matches!(
+            MotionBlur::new(0, 180.),
+            Err(Error::InvalidSubFrames(0))
+        ));
+        assert!(matches!(
+            MotionBlur::new(8, 400.),
+            Err(Error::InvalidShutterAngle(_))
+        ));
+        assert!(matches!(
+            MotionBlur::new(8, f32::NAN),
+            Err(Error::InvalidShutterAngle(_))
+        ));
+    }
+
+    #[test]
+    fn sub_frames_spread_over_the_shutter() {
+        let motion_blur = MotionBlur::new(4, 180.).unwrap();
+        assert_eq!(motion_blur.sub_frame_fraction(0), 0.);
+        assert_eq!(motion_blur.sub_frame_fraction(2), 0.25);
+    }
+}
@@ -0,0 +1,553 @@
+use macroquad::prelude::*;
+
+use crate::{
+    font::{get_font_bytes, FontHandle},
+    shapes::{fill_contours, with_opacity, Shape, ShapeStyle, Transform},
+    tessellate,
+};
+
+// Lines each curve is flattened into
+const CURVE_SEGMENTS: usize = 48;
+// Lines per full turn of an arc
+const ARC_SEGMENTS: usize = 96;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Segment {
+    MoveTo(Vec2),
+    LineTo(Vec2),
+    QuadTo {
+        control: Vec2,
+        to: Vec2,
+    },
+    CubicTo {
+        control1: Vec2,
+        control2: Vec2,
+        to: Vec2,
+    },
+    // Angles in degrees counter clockwise from the positive x axis, a negative sweep goes
+    // clockwise. A line joins the current point to the start of the arc.
+    Arc {
+        center: Vec2,
+        radius: f32,
+        start_angle: f32,
+        sweep: f32,
+    },
+    // Joins the current point back to the start of the subpath
+    Close,
+}
+
+// A subpath flattened into lines
+#[derive(Clone, Debug, PartialEq)]
+pub struct Polyline {
+    pub points: Vec<Vec2>,
+    pub closed: bool,
+}
+
+impl Polyline {
+    pub fn length(&self) -> f32 {
+        let open: f32 = self
+            .points
+            .windows(2)
+            .map(|pair| pair[0].distance(pair[1]))
+            .sum();
+        match (self.closed, self.points.first(), self.points.last()) {
+            (true, Some(first), Some(last)) => open + last.distance(*first),
+            _ => open,
+        }
+    }
+
+    // The points from start to end, including the closing segment of a closed polyline
+    fn unrolled(&self) -> Vec<Vec2> {
+        let mut points = self.points.clone();
+        if self.closed {
+            if let Some(first) = self.points.first() {
+                points.push(*first);
+            }
+        }
+        points
+    }
+}
+
+// A curve made of lines, bezier curves and arcs. Paths can be stroked, filled and revealed
+// progressively with draw_partial. Distances along the path are measured by arc length, so a
+// partial path grows at the same speed along straight and curved parts.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Path {
+    segments: Vec<Segment>,
+    transform: Transform,
+    style: ShapeStyle,
+}
+
+impl Default for Path {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Path {
+    pub fn new() -> Self {
+        Self {
+            segments: Vec::new(),
+            transform: Transform::default(),
+            style: ShapeStyle {
+                fill: None,
+                stroke: Some(WHITE),
+                ..Default::default()
+            },
+        }
+    }
+
+    // Joins the points with lines
+    pub fn from_points(points: &[Vec2]) -> Self {
+        let mut path = Self::new();
+        for (index, point) in points.iter().enumerate() {
+            path.push(if index == 0 {
+                Segment::MoveTo(*point)
+            } else {
+                Segment::LineTo(*point)
+            });
+        }
+        path
+    }
+
+    // The outline of a shape, with its transform and style
+    pub fn from_shape(shape: &impl Shape) -> Self {
+        let mut path = Self::from_points(&shape.local_outline());
+        if shape.is_closed() {
+            path.push(Segment::Close);
+        }
+        path.transform = *shape.transform();
+        path.style = shape.style().clone();
+        path
+    }
+
+    // The outlines of the glyphs of a line of text, starting at the origin on the baseline. The
+    // size is the height of the font's em square in world units. None if the font has no outlines
+    // or isn't loaded, a font of None uses the default font.
+    pub fn text(text: &str, size: f32, font: Option<FontHandle>) -> Option<Self> {
+        let bytes = get_font_bytes(font)?;
+        let face = ttf_parser::Face::from_slice(&bytes, 0).ok()?;
+
+        let mut builder = GlyphBuilder {
+            path: Self::new().fill(WHITE),
+            scale: size / face.units_per_em() as f32,
+            offset: vec2(0., 0.),
+        };
+        for character in text.chars() {
+            let Some(glyph) = face.glyph_index(character) else {
+                continue;
+            };
+            face.outline_glyph(glyph, &mut builder);
+            let advance = face.glyph_hor_advance(glyph).unwrap_or(0);
+            builder.offset.x += advance as f32 * builder.scale;
+        }
+        Some(builder.path)
+    }
+
+    pub fn move_to(mut self, to: Vec2) -> Self {
+        self.push(Segment::MoveTo(to));
+        self
+    }
+
+    pub fn line_to(mut self, to: Vec2) -> Self {
+        self.push(Segment::LineTo(to));
+        self
+    }
+
+    pub fn quad_to(mut self, control: Vec2, to: Vec2) -> Self {
+        self.push(Segment::QuadTo { control, to });
+        self
+    }
+
+    pub fn cubic_to(mut self, control1: Vec2, control2: Vec2, to: Vec2) -> Self {
+        self.push(Segment::CubicTo {
+            control1,
+            control2,
+            to,
+        });
+        self
+    }
+
+    pub fn arc(mut self, center: Vec2, radius: f32, start_angle: f32, sweep: f32) -> Self {
+        self.push(Segment::Arc {
+            center,
+            radius,
+            start_angle,
+            sweep,
+        });
+        self
+    }
+
+    pub fn close(mut self) -> Self {
+        self.push(Segment::Close);
+        self
+    }
+
+    pub fn push(&mut self, segment: Segment) {
+        self.segments.push(segment);
+    }
+
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    // The subpaths flattened into polylines in local coordinates
+    pub fn local_polylines(&self) -> Vec<Polyline> {
+        let mut polylines = Vec::new();
+        let mut current = Polyline {
+            points: Vec::new(),
+            closed: false,
+        };
+
+        for segment in &self.segments {
+            // Drawing without a current point starts where the segment starts
+            let from = current.points.last().copied();
+            match *segment {
+                Segment::MoveTo(to) => {
+                    finish(&mut polylines, &mut current);
+                    current.points.push(to);
+                }
+                Segment::LineTo(to) => current.points.push(to),
+                Segment::QuadTo { control, to } => {
+                    let from = from.unwrap_or(control);
+                    current.points.extend((1..=CURVE_SEGMENTS).map(|step| {
+                        let t = step as f32 / CURVE_SEGMENTS as f32;
+                        let a = from.lerp(control, t);
+                        let b = control.lerp(to, t);
+                        a.lerp(b, t)
+                    }));
+                }
+                Segment::CubicTo {
+                    control1,
+                    control2,
+                    to,
+                } => {
+                    let from = from.unwrap_or(control1);
+                    current.points.extend((1..=CURVE_SEGMENTS).map(|step| {
+                        let t = step as f32 / CURVE_SEGMENTS as f32;
+                        let u = 1. - t;
+                        from * u * u * u
+                            + control1 * 3. * u * u * t
+                            + control2 * 3. * u * t * t
+                            + to * t * t * t
+                    }));
+                }
+                Segment::Arc {
+                    center,
+                    radius,
+                    start_angle,
+                    sweep,
+                } => {
+                    let steps = ((sweep.abs() / 360. * ARC_SEGMENTS as f32).ceil() as usize).max(1);
+                    current.points.extend((0..=steps).map(|step| {
+                        let angle = start_angle + sweep * step as f32 / steps as f32;
+                        center + Vec2::from_angle(angle.to_radians()) * radius
+                    }));
+                }
+                Segment::Close => {
+                    current.closed = true;
+                    let start = current.points.first().copied();
+                    finish(&mut polylines, &mut current);
+                    // Drawing on after closing continues from the start of the closed subpath
+                    current.points.extend(start);
+                }
+            }
+        }
+        finish(&mut polylines, &mut current);
+        polylines
+    }
+
+    // The subpaths flattened into polylines in world coordinates
+    pub fn polylines(&self) -> Vec<Polyline> {
+        let mut polylines = self.local_polylines();
+        for polyline in &mut polylines {
+            for point in &mut polyline.points {
+                *point = self.transform.apply(*point);
+            }
+        }
+        polylines
+    }
+
+    // The total length in world units of all the subpaths
+    pub fn length(&self) -> f32 {
+        self.polylines().iter().map(Polyline::length).sum()
+    }
+
+    // The point the given fraction of the length along the path, None for an empty path
+    pub fn point_at(&self, t: f32) -> Option<Vec2> {
+        let polylines = self.partial(0., t.clamp(0., 1.));
+        match polylines.last() {
+            Some(polyline) => polyline.unrolled().last().copied(),
+            None => self.polylines().first()?.points.first().copied(),
+        }
+    }
+
+    // The part of the path from start to end as fractions of its length, in world coordinates.
+    // Subpaths follow each other, so halfway through a path of two equally long subpaths is the
+    // end of the first one.
+    pub fn partial(&self, start: f32, end: f32) -> Vec<Polyline> {
+        let polylines = self.polylines();
+        let total: f32 = polylines.iter().map(Polyline::length).sum();
+        let (start, end) = (start.clamp(0., 1.) * total, end.clamp(0., 1.) * total);
+        if end <= start {
+            return Vec::new();
+        }
+
+        let mut offset = 0.;
+        let mut pieces = Vec::new();
+        for polyline in polylines {
+            let length = polyline.length();
+            let (from, to) = (start - offset, end - offset);
+            offset += length;
+            if to <= 0. || from >= length {
+                continue;
+            }
+            if from <= 0. && to >= length {
+                pieces.push(polyline);
+                continue;
+            }
+
+            pieces.push(Polyline {
+                points: trim(&polyline.unrolled(), from.max(0.), to.min(length)),
+                closed: false,
+            });
+        }
+        pieces
+    }
+
+    // Reveals the part of the path from start to end as fractions of its length. Closed subpaths are
+    // filled with the even-odd rule, fading in with the revealed fraction.
+    pub fn draw_partial(&self, start: f32, end: f32) {
+        let (start, end) = (start.clamp(0., 1.), end.clamp(0., 1.));
+        if end <= start {
+            return;
+        }
+
+        if let Some(fill) = self.style.fill {
+            let color = with_opacity(fill, self.style.opacity * (end - start));
+            let polylines = self.polylines();
+            let contours: Vec<&[Vec2]> = polylines
+                .iter()
+                .filter(|polyline| polyline.closed)
+                .map(|polyline| polyline.points.as_slice())
+                .collect();
+            fill_contours(&contours, color);
+        }
+
+        for mesh in self.stroke_meshes(start, end) {
+            draw_mesh(&mesh);
+        }
+    }
+
+    // The stroke from start to end tessellated into meshes, empty without a stroke colour
+    pub fn stroke_meshes(&self, start: f32, end: f32) -> Vec<Mesh> {
+        let Some(stroke) = self.style.stroke else {
+            return Vec::new();
+        };
+        let color = with_opacity(stroke, self.style.opacity);
+        let style = self.style.stroke_style();
+        self.partial(start, end)
+            .iter()
+            .flat_map(|polyline| {
+                tessellate::stroke(&polyline.points, polyline.closed, &style, color)
+            })
+            .collect()
+    }
+}
+
+struct GlyphBuilder {
+    path: Path,
+    scale: f32,
+    offset: Vec2,
+}
+
+impl GlyphBuilder {
+    fn point(&self, x: f32, y: f32) -> Vec2 {
+        self.offset + vec2(x, y) * self.scale
+    }
+}
+
+// Font units point up like the world
+impl ttf_parser::OutlineBuilder for GlyphBuilder {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.path.push(Segment::MoveTo(self.point(x, y)));
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.path.push(Segment::LineTo(self.point(x, y)));
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        self.path.push(Segment::QuadTo {
+            control: self.point(x1, y1),
+            to: self.point(x, y),
+        });
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.path.push(Segment::CubicTo {
+            control1: self.point(x1, y1),
+            control2: self.point(x2, y2),
+            to: self.point(x, y),
+        });
+    }
+
+    fn close(&mut self) {
+        self.path.push(Segment::Close);
+    }
+}
+
+fn finish(polylines: &mut Vec<Polyline>, current: &mut Polyline) {
+    let mut polyline = std::mem::replace(
+        current,
+        Polyline {
+            points: Vec::new(),
+            closed: false,
+        },
+    );
+    if polyline.closed
+        && polyline.points.len() > 2
+        && polyline.points.first() == polyline.points.last()
+    {
+        polyline.points.pop();
+    }
+    if polyline.points.len() > 1 {
+        polylines.push(polyline);
+    }
+}
+
+// The part of an open polyline between two distances along it
+fn trim(points: &[Vec2], from: f32, to: f32) -> Vec<Vec2> {
+    let mut trimmed = Vec::new();
+    let mut offset = 0.;
+    for pair in points.windows(2) {
+        let length = pair[0].distance(pair[1]);
+        let (start, end) = (offset, offset + length);
+        offset = end;
+        if end <= from || length == 0. {
+            continue;
+        }
+        if start > to {
+            break;
+        }
+
+        if trimmed.is_empty() {
+            trimmed.push(pair[0].lerp(pair[1], ((from - start) / length).clamp(0., 1.)));
+        }
+        trimmed.push(pair[0].lerp(pair[1], ((to - start) / length).clamp(0., 1.)));
+    }
+    trimmed
+}
+
+impl Shape for Path {
+    fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn transform_mut(&mut self) -> &mut Transform {
+        &mut self.transform
+    }
+
+    fn style(&self) -> &ShapeStyle {
+        &self.style
+    }
+
+    fn style_mut(&mut self) -> &mut ShapeStyle {
+        &mut self.style
+    }
+
+    // Every subpath one after the other
+    fn local_outline(&self) -> Vec<Vec2> {
+        self.local_polylines()
+            .into_iter()
+            .flat_map(|polyline| polyline.points)
+            .collect()
+    }
+
+    fn is_closed(&self) -> bool {
+        let polylines = self.local_polylines();
+        !polylines.is_empty() && polylines.iter().all(|polyline| polyline.closed)
+    }
+
+    fn draw(&self) {
+        self.draw_partial(0., 1.);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(size: f32) -> Path {
+        Path::from_points(&[
+            vec2(0., 0.),
+            vec2(size, 0.),
+            vec2(size, size),
+            vec2(0., size),
+        ])
+        .close()
+    }
+
+    #[test]
+    fn length_includes_the_closing_segment() {
+        assert_eq!(square(10.).length(), 40.);
+        let open = Path::from_points(&[vec2(0., 0.), vec2(3., 4.), vec2(3., 10.)]);
+        assert_eq!(open.length(), 11.);
+        let circle = Path::new().arc(vec2(0., 0.), 1., 0., 360.);
+        assert!((circle.length() - std::f32::consts::TAU).abs() < 0.01);
+    }
+
+    #[test]
+    fn partial_follows_the_subpaths_in_order() {
+        let path = Path::new()
+            .move_to(vec2(0., 0.))
+            .line_to(vec2(10., 0.))
+            .move_to(vec2(0., 5.))
+            .line_to(vec2(10., 5.));
+
+        let first = path.partial(0., 0.5);
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].points, vec![vec2(0., 0.), vec2(10., 0.)]);
+
+        let across = path.partial(0.25, 0.75);
+        assert_eq!(across.len(), 2);
+        assert_eq!(across[0].points, vec![vec2(5., 0.), vec2(10., 0.)]);
+        assert_eq!(across[1].points, vec![vec2(0., 5.), vec2(5., 5.)]);
+
+        assert!(path.partial(0.6, 0.4).is_empty());
+    }
+
+    #[test]
+    fn partial_opens_closed_subpaths() {
+        let pieces = square(10.).partial(0.5, 1.);
+        assert_eq!(pieces.len(), 1);
+        assert!(!pieces[0].closed);
+        assert_eq!(
+            pieces[0].points,
+            vec![vec2(10., 10.), vec2(0., 10.), vec2(0., 0.)]
+        );
+        assert!(square(10.).partial(0., 1.)[0].closed);
+    }
+
+    #[test]
+    fn trim_cuts_inside_segments() {
+        let points = [vec2(0., 0.), vec2(10., 0.), vec2(10., 10.)];
+        assert_eq!(
+            trim(&points, 5., 15.),
+            vec![vec2(5., 0.), vec2(10., 0.), vec2(10., 5.)]
+        );
+        assert_eq!(trim(&points, 2., 4.), vec![vec2(2., 0.), vec2(4., 0.)]);
+        // Zero length segments are skipped rather than dividing by zero
+        let repeated = [vec2(0., 0.), vec2(0., 0.), vec2(4., 0.)];
+        assert_eq!(trim(&repeated, 1., 3.), vec![vec2(1., 0.), vec2(3., 0.)]);
+    }
+
+    #[test]
+    fn point_at_measures_along_the_path() {
+        let path = square(10.);
+        assert_eq!(path.point_at(0.), Some(vec2(0., 0.)));
+        assert_eq!(path.point_at(0.375), Some(vec2(10., 5.)));
+        assert_eq!(path.point_at(2.), Some(vec2(0., 0.)));
+        assert_eq!(Path::new().point_at(0.5), None);
+    }
+}
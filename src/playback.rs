@@ -0,0 +1,182 @@
+use macroquad::prelude::*;
+
+use crate::{clock::Clock, font::get_font};
+
+const NUMBER_KEYS: [KeyCode; 10] = [
+    KeyCode::Key0,
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+];
+
+#[derive(Copy, Clone)]
+pub struct TimelineStyle {
+    pub height: f32,
+    pub margin: f32,
+    pub bar_color: Color,
+    pub progress_color: Color,
+    pub text_color: Color,
+    pub font_size: u16,
+}
+
+impl Default for TimelineStyle {
+    fn default() -> Self {
+        Self {
+            height: 6.,
+            margin: 12.,
+            bar_color: Color::new(1., 1., 1., 0.2),
+            progress_color: ORANGE,
+            text_color: WHITE,
+            font_size: 16,
+        }
+    }
+}
+
+// Keyboard controls for previewing an animation, they are ignored while exporting:
+//     space        pause / resume
+//     left, right  pause and step one frame back or forward
+//     R            restart from t = 0
+//     0 - 9        seek to 0% - 90% of the duration, or to 0 - 9 seconds without a duration
+// With a duration the timeline can also be scrubbed by dragging along it with the mouse.
+pub struct PlaybackControls {
+    duration: Option<f32>,
+    show_timeline: bool,
+    style: TimelineStyle,
+}
+
+impl Default for PlaybackControls {
+    fn default() -> Self {
+        Self {
+            duration: None,
+            show_timeline: true,
+            style: TimelineStyle::default(),
+        }
+    }
+}
+
+impl PlaybackControls {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // The length of the animation in seconds, shown as the end of the timeline
+    pub fn duration(mut self, duration: f32) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    pub fn show_timeline(mut self, show_timeline: bool) -> Self {
+        self.show_timeline = show_timeline;
+        self
+    }
+
+    pub fn style(mut self, style: TimelineStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub(crate) fn handle_input(&self, clock: &mut Clock) {
+        if is_key_pressed(KeyCode::Space) {
+            clock.toggle_pause();
+        }
+        if is_key_pressed(KeyCode::Right) {
+            clock.set_paused(true);
+            clock.step_frames(1);
+        }
+        if is_key_pressed(KeyCode::Left) {
+            clock.set_paused(true);
+            clock.step_frames(-1);
+        }
+        if is_key_pressed(KeyCode::R) {
+            clock.restart();
+        }
+
+        for (number, key) in NUMBER_KEYS.iter().enumerate() {
+            if is_key_pressed(*key) {
+                let time = match self.duration {
+                    Some(duration) => duration * number as f32 / 10.,
+                    None => number as f32,
+                };
+                clock.seek(time);
+            }
+        }
+
+        if let Some(duration) = self.duration {
+            let bar = self.bar_rect();
+            let mouse: Vec2 = mouse_position().into();
+            // Generous vertical hit area since the bar itself is only a few pixels tall
+            let hit_area = Rect::new(bar.x, bar.y - bar.h * 2., bar.w, bar.h * 5.);
+            if self.show_timeline
+                && is_mouse_button_down(MouseButton::Left)
+                && hit_area.contains(mouse)
+            {
+                let progress = ((mouse.x - bar.x) / bar.w).clamp(0., 1.);
+                clock.seek(progress * duration);
+            }
+        }
+    }
+
+    // Drawn in screen space over the frame, never part of an export
+    pub(crate) fn draw_timeline(&self, clock: &Clock) {
+        if !self.show_timeline {
+            return;
+        }
+
+        let bar = self.bar_rect();
+        if let Some(duration) = self.duration {
+            let progress = (clock.time() / duration).clamp(0., 1.);
+            draw_rectangle(bar.x, bar.y, bar.w, bar.h, self.style.bar_color);
+            draw_rectangle(
+                bar.x,
+                bar.y,
+                bar.w * progress,
+                bar.h,
+                self.style.progress_color,
+            );
+            draw_circle(
+                bar.x + bar.w * progress,
+                bar.y + bar.h / 2.,
+                bar.h,
+                self.style.progress_color,
+            );
+        }
+
+        let mut text = match self.duration {
+            Some(duration) => format!("{:.2}s / {duration:.2}s", clock.time()),
+            None => format!("{:.2}s", clock.time()),
+        };
+        text += &format!("  frame {}", clock.frame());
+        if clock.is_paused() {
+            text += "  paused";
+        }
+
+        let font = get_font(None);
+        draw_text_ex(
+            &text,
+            bar.x,
+            bar.y - self.style.margin / 2.,
+            TextParams {
+                font: font.as_ref(),
+                font_size: self.style.font_size,
+                color: self.style.text_color,
+                ..Default::default()
+            },
+        );
+    }
+
+    fn bar_rect(&self) -> Rect {
+        let margin = self.style.margin;
+        Rect::new(
+            margin,
+            screen_height() - margin - self.style.height,
+            screen_width() - margin * 2.,
+            self.style.height,
+        )
+    }
+}
@@ -2,7 +2,15 @@ use std::ops::Range;
 
 use macroquad::prelude::*;
 
-use crate::{map, ui::draw_text_centered};
+use crate::{
+    error::Error,
+    font::FontHandle,
+    map,
+    path::{Path, Segment},
+    tessellate::{draw_polyline, DashPattern, LineCap, LineJoin, StrokeStyle},
+    ui::draw_text_centered,
+    Animation,
+};
 
 #[derive(Copy, Clone)]
 pub struct LabelStyle {
@@ -10,6 +18,7 @@ pub struct LabelStyle {
     pub color: Color,
     pub font_size: u16,
     pub decimal_places: usize,
+    pub font: Option<FontHandle>, // None uses the default font
 }
 
 impl Default for LabelStyle {
@@ -19,6 +28,7 @@ impl Default for LabelStyle {
             color: WHITE,
             font_size: 12,
             decimal_places: 2,
+            font: None,
         }
     }
 }
@@ -31,9 +41,19 @@ pub struct MarkerStyle {
 }
 impl Default for MarkerStyle {
     fn default() -> Self {
+        Self::with_units_per_pixel(1.)
+    }
+}
+
+impl MarkerStyle {
+    pub fn for_animation(animation: &Animation) -> Self {
+        Self::with_units_per_pixel(animation.units_per_pixel())
+    }
+
+    fn with_units_per_pixel(units_per_pixel: f32) -> Self {
         Self {
-            length: 5.,
-            thickness: 2.,
+            length: 5. * units_per_pixel,
+            thickness: 2. * units_per_pixel,
             color: WHITE,
         }
     }
@@ -63,26 +83,52 @@ pub enum GraphEndPointStyle {
 
 impl Default for GraphEndPointStyle {
     fn default() -> Self {
-        GraphEndPointStyle::Arrow { thickness: 7. }
+        Self::with_units_per_pixel(1.)
+    }
+}
+
+impl GraphEndPointStyle {
+    pub fn for_animation(animation: &Animation) -> Self {
+        Self::with_units_per_pixel(animation.units_per_pixel())
+    }
+
+    fn with_units_per_pixel(units_per_pixel: f32) -> Self {
+        GraphEndPointStyle::Arrow {
+            thickness: 7. * units_per_pixel,
+        }
     }
 }
 
+// Lengths and thicknesses are in world units. The defaults are sized for one unit per logical
+// pixel, the for_animation constructors size them in logical pixels of the animation's world.
 pub struct AxisStyle {
     pub tick_step: f32,
     pub tick_style: TickStyle,
     pub end_point_style: GraphEndPointStyle,
     pub line_thickness: f32,
     pub line_color: Color,
+    pub line_dash: Option<DashPattern>, // None for a solid axis
 }
 
 impl Default for AxisStyle {
     fn default() -> Self {
+        Self::with_units_per_pixel(1.)
+    }
+}
+
+impl AxisStyle {
+    pub fn for_animation(animation: &Animation) -> Self {
+        Self::with_units_per_pixel(animation.units_per_pixel())
+    }
+
+    fn with_units_per_pixel(units_per_pixel: f32) -> Self {
         AxisStyle {
             tick_step: 0.5,
             tick_style: TickStyle::default(),
-            end_point_style: GraphEndPointStyle::default(),
-            line_thickness: 3.,
+            end_point_style: GraphEndPointStyle::with_units_per_pixel(units_per_pixel),
+            line_thickness: 3. * units_per_pixel,
             line_color: WHITE,
+            line_dash: None,
         }
     }
 }
@@ -91,6 +137,20 @@ impl Default for AxisStyle {
 pub struct GraphStyle {
     pub x_style: AxisStyle,
     pub y_style: AxisStyle,
+    // How plotted lines are joined at each point and ended
+    pub plot_join: LineJoin,
+    pub plot_cap: LineCap,
+}
+
+impl GraphStyle {
+    // Both axes sized for the animation, see AxisStyle::for_animation
+    pub fn for_animation(animation: &Animation) -> Self {
+        Self {
+            x_style: AxisStyle::for_animation(animation),
+            y_style: AxisStyle::for_animation(animation),
+            ..Default::default()
+        }
+    }
 }
 
 pub struct Graph {
@@ -116,14 +176,26 @@ impl Graph {
         x_range: Range<f32>,
         y_range: Range<f32>,
     ) -> Self {
-        assert!(
-            x_range.start < x_range.end,
-            "The x_range must have start smaller than end"
-        );
-        assert!(
-            y_range.start < y_range.end,
-            "The y_range must have start smaller than end"
-        );
+        Self::try_new(world_center_pos, world_size, x_range, y_range)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_new(
+        world_center_pos: Vec2,
+        world_size: Vec2,
+        x_range: Range<f32>,
+        y_range: Range<f32>,
+    ) -> Result<Self, Error> {
+        for (name, range) in [("x_range", &x_range), ("y_range", &y_range)] {
+            // Also empty when either bound is NaN
+            if range.is_empty() {
+                return Err(Error::InvalidRange {
+                    name,
+                    start: range.start,
+                    end: range.end,
+                });
+            }
+        }
 
         let mut graph = Self {
             world_center_pos,
@@ -150,7 +222,7 @@ impl Graph {
             graph.axes_pos.x = graph.world_min_coords.x;
         }
 
-        graph
+        Ok(graph)
     }
     pub fn style(mut self, style: GraphStyle) -> Self {
         self.style = style;
@@ -166,22 +238,24 @@ impl Graph {
 
     pub fn draw_axes(&self) {
         // Draw X Axis
-        draw_line(
-            self.world_min_coords.x,
-            self.axes_pos.y,
-            self.world_max_coords.x,
-            self.axes_pos.y,
-            self.style.y_style.line_thickness,
+        draw_polyline(
+            &[
+                vec2(self.world_min_coords.x, self.axes_pos.y),
+                vec2(self.world_max_coords.x, self.axes_pos.y),
+            ],
+            false,
+            &axis_stroke(&self.style.y_style),
             self.style.y_style.line_color,
         );
 
         // Draw Y Axis
-        draw_line(
-            self.axes_pos.x,
-            self.world_min_coords.y,
-            self.axes_pos.x,
-            self.world_max_coords.y,
-            self.style.x_style.line_thickness,
+        draw_polyline(
+            &[
+                vec2(self.axes_pos.x, self.world_min_coords.y),
+                vec2(self.axes_pos.x, self.world_max_coords.y),
+            ],
+            false,
+            &axis_stroke(&self.style.x_style),
             self.style.x_style.line_color,
         );
 
@@ -396,6 +470,7 @@ impl Graph {
                 label_style.pos_offset.y,
                 label_style.font_size,
                 label_style.color,
+                label_style.font,
             )
         }
     }
@@ -491,21 +566,65 @@ impl Graph {
     }
 
     pub fn plot_line_vec(&self, pts: &[Vec2], thickness: f32, color: Color) {
-        pts.windows(2).for_each(|slice| {
-            let pt_a = self.graph_to_world(slice[0]);
-            let pt_b = self.graph_to_world(slice[1]);
-            self.plot_line_world(&pt_a, &pt_b, thickness, color);
-        });
+        let world_pts: Vec<Vec2> = pts.iter().map(|pt| self.graph_to_world(*pt)).collect();
+        self.plot_line_world(&world_pts, thickness, color, None);
     }
 
     pub fn plot_line_xy(&self, x: &[f32], y: &[f32], thickness: f32, color: Color) {
-        x.windows(2).zip(y.windows(2)).for_each(|(xs, ys)| {
-            let pt_a = self.graph_to_world(vec2(xs[0], ys[0]));
-            let pt_b = self.graph_to_world(vec2(xs[1], ys[1]));
+        let world_pts = self.xy_to_world(x, y);
+        self.plot_line_world(&world_pts, thickness, color, None);
+    }
+
+    // Dash lengths are in world units along the line
+    pub fn plot_line_vec_dashed(
+        &self,
+        pts: &[Vec2],
+        thickness: f32,
+        color: Color,
+        dash: &DashPattern,
+    ) {
+        let world_pts: Vec<Vec2> = pts.iter().map(|pt| self.graph_to_world(*pt)).collect();
+        self.plot_line_world(&world_pts, thickness, color, Some(dash));
+    }
+
+    pub fn plot_line_xy_dashed(
+        &self,
+        x: &[f32],
+        y: &[f32],
+        thickness: f32,
+        color: Color,
+        dash: &DashPattern,
+    ) {
+        let world_pts = self.xy_to_world(x, y);
+        self.plot_line_world(&world_pts, thickness, color, Some(dash));
+    }
 
-            self.plot_line_world(&pt_a, &pt_b, thickness, color);
-        })
+    // Guide lines from a point straight down or up to the x axis and across to the y axis
+    pub fn plot_guides(&self, pt: &Vec2, thickness: f32, color: Color, dash: &DashPattern) {
+        let world_pt = self.graph_to_world(*pt);
+        for end in [
+            vec2(world_pt.x, self.axes_pos.y),
+            vec2(self.axes_pos.x, world_pt.y),
+        ] {
+            // A point on an axis has no guide to it
+            if end != world_pt {
+                self.plot_line_world(&[world_pt, end], thickness, color, Some(dash));
+            }
+        }
     }
+
+    // The line plot_line_vec draws as a path in world coordinates, for drawing it progressively.
+    // Segments with neither end on the graph are left out, splitting the path.
+    pub fn line_path_vec(&self, pts: &[Vec2]) -> Path {
+        let world_pts: Vec<Vec2> = pts.iter().map(|pt| self.graph_to_world(*pt)).collect();
+        self.line_path_world(&world_pts)
+    }
+
+    pub fn line_path_xy(&self, x: &[f32], y: &[f32]) -> Path {
+        let world_pts = self.xy_to_world(x, y);
+        self.line_path_world(&world_pts)
+    }
+
     pub fn plot_pt_vec(&self, pt: &Vec2, radius: f32, color: Color) {
         let pt = self.graph_to_world(*pt);
         if !self.world_pt_in_world_bb(&pt) {
@@ -523,16 +642,69 @@ impl Graph {
 
         draw_circle(pt.x, pt.y, radius, color);
     }
-    fn plot_line_world(&self, pt_a: &Vec2, pt_b: &Vec2, thickness: f32, color: Color) {
-        if !self.world_pt_in_world_bb(pt_a) && !self.world_pt_in_world_bb(pt_b) {
-            // Neither point is on the graph so bail this iteration
-            return;
+    // Each run of the line on the graph is tessellated as one mesh so thick and translucent lines
+    // join cleanly
+    fn plot_line_world(
+        &self,
+        pts: &[Vec2],
+        thickness: f32,
+        color: Color,
+        dash: Option<&DashPattern>,
+    ) {
+        let style = StrokeStyle {
+            dash: dash.cloned(),
+            ..StrokeStyle::new(thickness)
+                .join(self.style.plot_join)
+                .cap(self.style.plot_cap)
+        };
+        for run in self.visible_runs(pts) {
+            draw_polyline(&run, false, &style, color);
         }
-        // TODO: if a is in the graph but b isn't then clamp b at the nearest intersection point with
-        // the nearest border. Vice versa for b. If both are off then don't draw anything
+    }
+
+    fn xy_to_world(&self, x: &[f32], y: &[f32]) -> Vec<Vec2> {
+        x.iter()
+            .zip(y)
+            .map(|(x, y)| self.graph_to_world(vec2(*x, *y)))
+            .collect()
+    }
 
-        draw_line(pt_a.x, pt_a.y, pt_b.x, pt_b.y, thickness, color);
+    fn line_path_world(&self, pts: &[Vec2]) -> Path {
+        let mut path = Path::new();
+        for run in self.visible_runs(pts) {
+            for (index, pt) in run.into_iter().enumerate() {
+                path.push(if index == 0 {
+                    Segment::MoveTo(pt)
+                } else {
+                    Segment::LineTo(pt)
+                });
+            }
+        }
+        path
     }
+
+    // Splits a line in world coordinates into the runs of segments with at least one end on the
+    // graph, other segments are left out
+    fn visible_runs(&self, pts: &[Vec2]) -> Vec<Vec<Vec2>> {
+        // TODO: if a is in the graph but b isn't then clamp b at the nearest intersection point with
+        // the nearest border. Vice versa for b.
+        let mut runs: Vec<Vec<Vec2>> = Vec::new();
+        let mut pen = None;
+        for slice in pts.windows(2) {
+            let (pt_a, pt_b) = (slice[0], slice[1]);
+            if !self.world_pt_in_world_bb(&pt_a) && !self.world_pt_in_world_bb(&pt_b) {
+                pen = None;
+                continue;
+            }
+            match runs.last_mut() {
+                Some(run) if pen == Some(pt_a) => run.push(pt_b),
+                _ => runs.push(vec![pt_a, pt_b]),
+            }
+            pen = Some(pt_b);
+        }
+        runs
+    }
+
     fn world_pt_in_world_bb(&self, pt: &Vec2) -> bool {
         pt.x >= self.world_min_coords.x
             && pt.y >= self.world_min_coords.y
@@ -540,3 +712,10 @@ impl Graph {
             && pt.y <= self.world_max_coords.y
     }
 }
+
+fn axis_stroke(style: &AxisStyle) -> StrokeStyle {
+    StrokeStyle {
+        dash: style.line_dash.clone(),
+        ..StrokeStyle::new(style.line_thickness)
+    }
+}
@@ -0,0 +1,682 @@
+use macroquad::prelude::*;
+
+use crate::error::Error;
+
+pub enum PostEffect {
+    Fxaa,
+    // Adds a blurred glow around everything brighter than the threshold luminance
+    Bloom {
+        threshold: f32,
+        intensity: f32,
+        radius: f32, // Pixels between blur samples
+    },
+    Vignette {
+        strength: f32,
+        radius: f32, // Distance from the centre where darkening starts, 0.5 is the frame edge
+    },
+    FilmGrain {
+        amount: f32,
+    },
+    ColorGrade {
+        exposure: f32, // Stops, 1.0 doubles the brightness
+        contrast: f32,
+        saturation: f32,
+        tint: Color,
+    },
+    ChromaticAberration {
+        amount: f32, // Pixels the red and blue channels are split by at the frame edge
+    },
+    // A GLSL 100 fragment shader. The `Texture` sampler, `uv` varying and the `resolution` and
+    // `time` uniforms are always available, any extra uniforms must be listed.
+    Custom {
+        fragment: String,
+        uniforms: Vec<(String, UniformType)>,
+    },
+}
+
+impl PostEffect {
+    pub fn bloom() -> Self {
+        PostEffect::Bloom {
+            threshold: 0.6,
+            intensity: 1.,
+            radius: 2.,
+        }
+    }
+
+    pub fn vignette() -> Self {
+        PostEffect::Vignette {
+            strength: 0.5,
+            radius: 0.4,
+        }
+    }
+
+    pub fn film_grain() -> Self {
+        PostEffect::FilmGrain { amount: 0.05 }
+    }
+
+    pub fn color_grade() -> Self {
+        PostEffect::ColorGrade {
+            exposure: 0.,
+            contrast: 1.,
+            saturation: 1.,
+            tint: WHITE,
+        }
+    }
+
+    pub fn chromatic_aberration() -> Self {
+        PostEffect::ChromaticAberration { amount: 2. }
+    }
+
+    fn fragment_shader(&self) -> &str {
+        match self {
+            PostEffect::Fxaa => FXAA_FRAGMENT_SHADER,
+            PostEffect::Bloom { .. } => BLOOM_FRAGMENT_SHADER,
+            PostEffect::Vignette { .. } => VIGNETTE_FRAGMENT_SHADER,
+            PostEffect::FilmGrain { .. } => FILM_GRAIN_FRAGMENT_SHADER,
+            PostEffect::ColorGrade { .. } => COLOR_GRADE_FRAGMENT_SHADER,
+            PostEffect::ChromaticAberration { .. } => CHROMATIC_ABERRATION_FRAGMENT_SHADER,
+            PostEffect::Custom { fragment, .. } => fragment,
+        }
+    }
+
+    fn uniforms(&self) -> Vec<(String, UniformType)> {
+        let mut uniforms = vec![
+            ("resolution".to_string(), UniformType::Float2),
+            ("time".to_string(), UniformType::Float1),
+        ];
+        let effect_uniforms: &[(&str, UniformType)] = match self {
+            PostEffect::Fxaa => &[],
+            PostEffect::Bloom { .. } => &[
+                ("threshold", UniformType::Float1),
+                ("intensity", UniformType::Float1),
+                ("radius", UniformType::Float1),
+            ],
+            PostEffect::Vignette { .. } => &[
+                ("strength", UniformType::Float1),
+                ("radius", UniformType::Float1),
+            ],
+            PostEffect::FilmGrain { .. } => &[("amount", UniformType::Float1)],
+            PostEffect::ColorGrade { .. } => &[
+                ("exposure", UniformType::Float1),
+                ("contrast", UniformType::Float1),
+                ("saturation", UniformType::Float1),
+                ("tint", UniformType::Float4),
+            ],
+            PostEffect::ChromaticAberration { .. } => &[("amount", UniformType::Float1)],
+            PostEffect::Custom {
+                uniforms: custom, ..
+            } => {
+                uniforms.extend(
+                    custom
+                        .iter()
+                        .filter(|(name, _)| name != "resolution" && name != "time")
+                        .cloned(),
+                );
+                &[]
+            }
+        };
+        uniforms.extend(
+            effect_uniforms
+                .iter()
+                .map(|(name, uniform_type)| (name.to_string(), *uniform_type)),
+        );
+
+        uniforms
+    }
+
+    fn set_uniforms(&self, material: &Material) {
+        match *self {
+            PostEffect::Bloom {
+                threshold,
+                intensity,
+                radius,
+            } => {
+                material.set_uniform("threshold", threshold);
+                material.set_uniform("intensity", intensity);
+                material.set_uniform("radius", radius);
+            }
+            PostEffect::Vignette { strength, radius } => {
+                material.set_uniform("strength", strength);
+                material.set_uniform("radius", radius);
+            }
+            PostEffect::FilmGrain { amount } | PostEffect::ChromaticAberration { amount } => {
+                material.set_uniform("amount", amount);
+            }
+            PostEffect::ColorGrade {
+                exposure,
+                contrast,
+                saturation,
+                tint,
+            } => {
+                material.set_uniform("exposure", exposure);
+                material.set_uniform("contrast", contrast);
+                material.set_uniform("saturation", saturation);
+                material.set_uniform("tint", tint.to_vec());
+            }
+            PostEffect::Fxaa | PostEffect::Custom { .. } => (),
+        }
+    }
+}
+
+pub struct PostPass {
+    name: String,
+    effect: PostEffect,
+    material: Material,
+    enabled: bool,
+}
+
+impl PostPass {
+    fn new(name: &str, effect: PostEffect) -> Result<Self, Error> {
+        let material = load_material(
+            ShaderSource::Glsl {
+                vertex: POST_VERTEX_SHADER,
+                fragment: effect.fragment_shader(),
+            },
+            MaterialParams {
+                uniforms: effect.uniforms(),
+                ..Default::default()
+            },
+        )
+        .map_err(|source| Error::Shader {
+            name: name.to_owned(),
+            source,
+        })?;
+
+        Ok(Self {
+            name: name.to_owned(),
+            effect,
+            material,
+            enabled: true,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn effect(&self) -> &PostEffect {
+        &self.effect
+    }
+
+    // Changes the parameters of the effect. Changing it to a different kind of effect has no
+    // effect since the shader is only compiled when the pass is added.
+    pub fn effect_mut(&mut self) -> &mut PostEffect {
+        &mut self.effect
+    }
+
+    // Sets the value of one of the extra uniforms of a custom effect
+    pub fn set_uniform<T>(&self, name: &str, value: T) {
+        self.material.set_uniform(name, value);
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct PostPipeline {
+    passes: Vec<PostPass>,
+    targets: Vec<RenderTarget>,
+}
+
+impl PostPipeline {
+    pub(crate) fn add(&mut self, name: &str, effect: PostEffect) -> Result<(), Error> {
+        self.passes.push(PostPass::new(name, effect)?);
+        Ok(())
+    }
+
+    pub(crate) fn remove(&mut self, name: &str) {
+        self.passes.retain(|pass| pass.name != name);
+    }
+
+    pub(crate) fn get(&self, name: &str) -> Option<&PostPass> {
+        self.passes.iter().find(|pass| pass.name == name)
+    }
+
+    pub(crate) fn get_mut(&mut self, name: &str) -> Option<&mut PostPass> {
+        self.passes.iter_mut().find(|pass| pass.name == name)
+    }
+
+    // Runs every enabled pass in order, ping ponging between two intermediate render targets.
+    // Returns the texture holding the final image.
+    pub(crate) fn apply(
+        &mut self,
+        source: &Texture2D,
+        time: f32,
+        filter_mode: FilterMode,
+    ) -> Texture2D {
+        if !self.passes.iter().any(|pass| pass.enabled) {
+            return source.clone();
+        }
+
+        let size = source.size();
+        if self
+            .targets
+            .first()
+            .is_none_or(|target| target.texture.size() != size)
+        {
+            for target in &self.targets {
+                target.delete();
+            }
+            self.targets = (0..2)
+                .map(|_| render_target(size.x as u32, size.y as u32))
+                .collect();
+        }
+        for target in &self.targets {
+            target.texture.set_filter(filter_mode);
+        }
+
+        let mut input = source.clone();
+        for (index, pass) in self.passes.iter().filter(|pass| pass.enabled).enumerate() {
+            let target = &self.targets[index % 2];
+
+            // Unlike Camera2D::from_display_rect this keeps the first row of the texture at the
+            // top, so every target has the same orientation as the scene render target
+            set_camera(&Camera2D {
+                target: size / 2.,
+                zoom: vec2(2. / size.x, 2. / size.y),
+                render_target: Some(target.clone()),
+                ..Default::default()
+            });
+
+            pass.material.set_uniform("resolution", size);
+            pass.material.set_uniform("time", time);
+            pass.effect.set_uniforms(&pass.material);
+            gl_use_material(&pass.material);
+
+            draw_texture_ex(
+                &input,
+                0.,
+                0.,
+                WHITE,
+                DrawTextureParams {
+                    dest_size: Some(size),
+                    ..Default::default()
+                },
+            );
+
+            gl_use_default_material();
+            input = target.texture.clone();
+        }
+
+        set_default_camera();
+        input
+    }
+}
+
+pub(crate) const POST_VERTEX_SHADER: &str = r#"#version 100
+attribute vec3 position;
+attribute vec2 texcoord;
+
+varying lowp vec2 uv;
+
+uniform mat4 Model;
+uniform mat4 Projection;
+
+void main() {
+    gl_Position = Projection * Model * vec4(position, 1);
+    uv = texcoord;
+}"#;
+const FXAA_FRAGMENT_SHADER: &str = r#"
+#version 100
+precision lowp float;
+
+// THIS CODE IS THANKS TO: 
+// https://blog.simonrodriguez.fr/articles/2016/07/implementing_fxaa.html
+
+varying vec2 uv;
+
+// UNIFORMS
+uniform sampler2D Texture;
+uniform vec2 resolution;
+uniform float time;
+
+// CONSTANTS
+const float EDGE_THRESHOLD_MIN = 0.0312;
+const float EDGE_THRESHOLD_MAX = 0.125;
+const int ITERATIONS = 12;
+const float SUBPIXEL_QUALITY = 0.75;
+
+float rgb2luma(vec3 rgb){
+    return sqrt(dot(rgb, vec3(0.299, 0.587, 0.114)));
+}
+
+float get_quality(int i) {
+    float quality = 0.0;
+    if(i < 6) {
+       quality = 1.0; 
+    } else if(i == 6) {
+       quality = 1.5; 
+    } else if(i > 6 && i < 10) {
+       quality = 2.0; 
+    } else if(i == 10) {
+       quality = 4.0; 
+    } else {
+        quality = 8.0;
+    }
+
+    return quality;
+}
+
+void main() {
+    vec2 inverseScreenSize = vec2(1.0/resolution.x, 1.0/resolution.y);
+    vec3 colorCenter = texture2D(Texture,uv).rgb;
+
+    // Luma at the current fragment
+    float lumaCenter = rgb2luma(colorCenter);
+
+    // Directions
+    vec2 up = vec2(0., -inverseScreenSize.y);
+    vec2 down = vec2(0., inverseScreenSize.y);
+    vec2 left = vec2(-inverseScreenSize.x, 0.);
+    vec2 right = vec2(inverseScreenSize.x, 0.);
+
+    // Luma at the four direct neighbours of the current fragment.
+    float lumaDown = rgb2luma(texture2D(Texture, uv + down).rgb);
+    float lumaUp = rgb2luma(texture2D(Texture,uv + up).rgb);
+    float lumaLeft = rgb2luma(texture2D(Texture,uv + left).rgb);
+    float lumaRight = rgb2luma(texture2D(Texture,uv + right).rgb);
+
+    // Find the maximum and minimum luma around the current fragment.
+    float lumaMin = min(lumaCenter,min(min(lumaDown,lumaUp),min(lumaLeft,lumaRight)));
+    float lumaMax = max(lumaCenter,max(max(lumaDown,lumaUp),max(lumaLeft,lumaRight)));
+
+    // Compute the delta.
+    float lumaRange = lumaMax - lumaMin;
+
+    // If the luma variation is lower that a threshold (or if we are in a really dark area), we are not on an edge, don't perform any AA.
+    if(lumaRange < max(EDGE_THRESHOLD_MIN,lumaMax*EDGE_THRESHOLD_MAX)){
+        gl_FragColor = texture2D(Texture, uv);
+        return;
+    }
+
+    // Query the 4 remaining corners lumas.
+    float lumaDownLeft = rgb2luma(texture2D(Texture,uv + down + left).rgb);
+    float lumaUpRight = rgb2luma(texture2D(Texture,uv + up + right).rgb);
+    float lumaUpLeft = rgb2luma(texture2D(Texture,uv + up + left).rgb);
+    float lumaDownRight = rgb2luma(texture2D(Texture,uv + down + right).rgb);
+
+    // Combine the four edges lumas (using intermediary variables for future computations with the same values).
+    float lumaDownUp = lumaDown + lumaUp;
+    float lumaLeftRight = lumaLeft + lumaRight;
+
+    // Same for corners
+    float lumaLeftCorners = lumaDownLeft + lumaUpLeft;
+    float lumaDownCorners = lumaDownLeft + lumaDownRight;
+    float lumaRightCorners = lumaDownRight + lumaUpRight;
+    float lumaUpCorners = lumaUpRight + lumaUpLeft;
+
+    // Compute an estimation of the gradient along the horizontal and vertical axis.
+    float edgeHorizontal =  abs(-2.0 * lumaLeft + lumaLeftCorners)  + abs(-2.0 * lumaCenter + lumaDownUp ) * 2.0    + abs(-2.0 * lumaRight + lumaRightCorners);
+    float edgeVertical =    abs(-2.0 * lumaUp + lumaUpCorners)      + abs(-2.0 * lumaCenter + lumaLeftRight) * 2.0  + abs(-2.0 * lumaDown + lumaDownCorners);
+
+    // Is the local edge horizontal or vertical ?
+    bool isHorizontal = (edgeHorizontal >= edgeVertical);
+
+    // Select the two neighboring texels lumas in the opposite direction to the local edge.
+    float luma1 = isHorizontal ? lumaDown : lumaLeft;
+    float luma2 = isHorizontal ? lumaUp : lumaRight;
+    // Compute gradients in this direction.
+    float gradient1 = luma1 - lumaCenter;
+    float gradient2 = luma2 - lumaCenter;
+
+    // Which direction is the steepest ?
+    bool is1Steepest = abs(gradient1) >= abs(gradient2);
+
+    // Gradient in the corresponding direction, normalized.
+    float gradientScaled = 0.25*max(abs(gradient1),abs(gradient2));
+
+    // Choose the step size (one pixel) according to the edge direction.
+    float stepLength = isHorizontal ? inverseScreenSize.y : inverseScreenSize.x;
+
+    // Average luma in the correct direction.
+    float lumaLocalAverage = 0.0;
+
+    if(is1Steepest){
+        // Switch the direction
+        stepLength = - stepLength;
+        lumaLocalAverage = 0.5*(luma1 + lumaCenter);
+    } else {
+        lumaLocalAverage = 0.5*(luma2 + lumaCenter);
+    }
+
+    // Shift UV in the correct direction by half a pixel.
+    vec2 currentUv = uv;
+    if(isHorizontal){
+        currentUv.y += stepLength * 0.5;
+    } else {
+        currentUv.x += stepLength * 0.5;
+    }
+
+    // Compute offset (for each iteration step) in the right direction.
+    vec2 offset = isHorizontal ? vec2(inverseScreenSize.x,0.0) : vec2(0.0,inverseScreenSize.y);
+    // Compute UVs to explore on each side of the edge, orthogonally. The QUALITY allows us to step faster.
+    vec2 uv1 = currentUv - offset;
+    vec2 uv2 = currentUv + offset;
+
+    // Read the lumas at both current extremities of the exploration segment, and compute the delta wrt to the local average luma.
+    float lumaEnd1 = rgb2luma(texture2D(Texture,uv1).rgb);
+    float lumaEnd2 = rgb2luma(texture2D(Texture,uv2).rgb);
+    lumaEnd1 -= lumaLocalAverage;
+    lumaEnd2 -= lumaLocalAverage;
+
+    // If the luma deltas at the current extremities are larger than the local gradient, we have reached the side of the edge.
+    bool reached1 = abs(lumaEnd1) >= gradientScaled;
+    bool reached2 = abs(lumaEnd2) >= gradientScaled;
+    bool reachedBoth = reached1 && reached2;
+
+    // If the side is not reached, we continue to explore in this direction.
+    if(!reached1){
+        uv1 -= offset;
+    }
+    if(!reached2){
+        uv2 += offset;
+    }
+
+    // If both sides have not been reached, continue to explore.
+    if(!reachedBoth){
+
+        for(int i = 2; i < ITERATIONS; i++){
+            // If needed, read luma in 1st direction, compute delta.
+            if(!reached1){
+                lumaEnd1 = rgb2luma(texture2D(Texture, uv1).rgb);
+                lumaEnd1 = lumaEnd1 - lumaLocalAverage;
+            }
+            // If needed, read luma in opposite direction, compute delta.
+            if(!reached2){
+                lumaEnd2 = rgb2luma(texture2D(Texture, uv2).rgb);
+                lumaEnd2 = lumaEnd2 - lumaLocalAverage;
+            }
+            // If the luma deltas at the current extremities is larger than the local gradient, we have reached the side of the edge.
+            reached1 = abs(lumaEnd1) >= gradientScaled;
+            reached2 = abs(lumaEnd2) >= gradientScaled;
+            reachedBoth = reached1 && reached2;
+
+            // If the side is not reached, we continue to explore in this direction, with a variable quality.
+            if(!reached1){
+                uv1 -= offset * get_quality(i);
+            }
+            if(!reached2){
+                uv2 += offset * get_quality(i);
+            }
+
+            // If both sides have been reached, stop the exploration.
+            if(reachedBoth){ break;}
+        }
+    }
+
+    // Compute the distances to each extremity of the edge.
+    float distance1 = isHorizontal ? (uv.x - uv1.x) : (uv.y - uv1.y);
+    float distance2 = isHorizontal ? (uv2.x - uv.x) : (uv2.y - uv.y);
+
+    // which direction is the extremity of the edge closer ?
+    bool isDirection1 = distance1 < distance2;
+    float distanceFinal = min(distance1, distance2);
+
+    // Length of the edge.
+    float edgeThickness = (distance1 + distance2);
+
+    // UV offset: read in the direction of the closest side of the edge.
+    float pixelOffset = - distanceFinal / edgeThickness + 0.5;
+
+    // Is the luma at center smaller than the local average ?
+    bool isLumaCenterSmaller = lumaCenter < lumaLocalAverage;
+
+    // If the luma at center is smaller than at its neighbour, the delta luma at each end should be positive (same variation).
+    // (in the direction of the closer side of the edge.)
+    bool correctVariation = ((isDirection1 ? lumaEnd1 : lumaEnd2) < 0.0) != isLumaCenterSmaller;
+
+    // If the luma variation is incorrect, do not offset.
+    float finalOffset = correctVariation ? pixelOffset : 0.0;
+
+    // Sub-pixel shifting
+    // Full weighted average of the luma over the 3x3 neighborhood.
+    float lumaAverage = (1.0/12.0) * (2.0 * (lumaDownUp + lumaLeftRight) + lumaLeftCorners + lumaRightCorners);
+    // Ratio of the delta between the global average and the center luma, over the luma range in the 3x3 neighborhood.
+    float subPixelOffset1 = clamp(abs(lumaAverage - lumaCenter)/lumaRange,0.0,1.0);
+    float subPixelOffset2 = (-2.0 * subPixelOffset1 + 3.0) * subPixelOffset1 * subPixelOffset1;
+    // Compute a sub-pixel offset based on this delta.
+    float subPixelOffsetFinal = subPixelOffset2 * subPixelOffset2 * SUBPIXEL_QUALITY;
+
+    // Pick the biggest of the two offsets.
+    finalOffset = max(finalOffset,subPixelOffsetFinal);
+
+    // Compute the final UV coordinates.
+    vec2 finalUv = uv;
+    if(isHorizontal){
+        finalUv.y += finalOffset * stepLength;
+    } else {
+        finalUv.x += finalOffset * stepLength;
+    }
+
+    // Read the color at the new UV coordinates, and use it. Alpha is filtered along with the
+    // colour so transparent frames keep antialiased edges.
+    gl_FragColor = texture2D(Texture,finalUv);
+}"#;
+
+const BLOOM_FRAGMENT_SHADER: &str = r#"#version 100
+precision mediump float;
+
+varying vec2 uv;
+
+uniform sampler2D Texture;
+uniform vec2 resolution;
+uniform float threshold;
+uniform float intensity;
+uniform float radius;
+
+const int SAMPLES = 4;
+
+float luma(vec3 rgb) {
+    return dot(rgb, vec3(0.2126, 0.7152, 0.0722));
+}
+
+void main() {
+    vec4 color = texture2D(Texture, uv);
+
+    // Gaussian weighted sum of only the parts of the neighbourhood above the threshold
+    vec3 glow = vec3(0.0);
+    float total_weight = 0.0;
+    for (int x = -SAMPLES; x <= SAMPLES; x++) {
+        for (int y = -SAMPLES; y <= SAMPLES; y++) {
+            vec2 offset = vec2(float(x), float(y)) * radius / resolution;
+            vec3 sample_color = texture2D(Texture, uv + offset).rgb;
+            float brightness = luma(sample_color);
+            float weight = exp(-float(x * x + y * y) / float(SAMPLES * SAMPLES));
+
+            glow += sample_color * max(brightness - threshold, 0.0) / max(brightness, 0.0001) * weight;
+            total_weight += weight;
+        }
+    }
+
+    // The glow is light added on top, it makes transparent areas it covers opaque enough to show it
+    vec3 rgb = color.rgb + intensity * glow / total_weight;
+    float alpha = max(color.a, max(rgb.r, max(rgb.g, rgb.b)));
+    gl_FragColor = vec4(rgb, min(alpha, 1.0));
+}"#;
+
+const VIGNETTE_FRAGMENT_SHADER: &str = r#"#version 100
+precision mediump float;
+
+varying vec2 uv;
+
+uniform sampler2D Texture;
+uniform float strength;
+uniform float radius;
+
+void main() {
+    vec4 color = texture2D(Texture, uv);
+    float dist = distance(uv, vec2(0.5));
+    float darken = strength * smoothstep(radius, radius + 0.5, dist);
+
+    gl_FragColor = vec4(color.rgb * (1.0 - darken), color.a);
+}"#;
+
+const FILM_GRAIN_FRAGMENT_SHADER: &str = r#"#version 100
+precision mediump float;
+
+varying vec2 uv;
+
+uniform sampler2D Texture;
+uniform vec2 resolution;
+uniform float time;
+uniform float amount;
+
+float random(vec2 co) {
+    return fract(sin(dot(co, vec2(12.9898, 78.233))) * 43758.5453);
+}
+
+void main() {
+    vec4 color = texture2D(Texture, uv);
+    float noise = random(floor(uv * resolution) + fract(time) * 100.0) - 0.5;
+
+    // Colours are premultiplied so the grain fades out with the alpha
+    gl_FragColor = vec4(color.rgb + noise * amount * color.a, color.a);
+}"#;
+
+const COLOR_GRADE_FRAGMENT_SHADER: &str = r#"#version 100
+precision mediump float;
+
+varying vec2 uv;
+
+uniform sampler2D Texture;
+uniform float exposure;
+uniform float contrast;
+uniform float saturation;
+uniform vec4 tint;
+
+void main() {
+    vec4 color = texture2D(Texture, uv);
+    // Graded without the premultiplied alpha so transparent areas don't change colour
+    vec3 rgb = color.a > 0.0 ? color.rgb / color.a : vec3(0.0);
+    rgb = rgb * pow(2.0, exposure);
+    rgb = (rgb - 0.5) * contrast + 0.5;
+    float luma = dot(rgb, vec3(0.2126, 0.7152, 0.0722));
+    rgb = mix(vec3(luma), rgb, saturation) * tint.rgb;
+
+    gl_FragColor = vec4(clamp(rgb, 0.0, 1.0) * color.a, color.a);
+}"#;
+
+const CHROMATIC_ABERRATION_FRAGMENT_SHADER: &str = r#"#version 100
+precision mediump float;
+
+varying vec2 uv;
+
+uniform sampler2D Texture;
+uniform vec2 resolution;
+uniform float amount;
+
+void main() {
+    // The split grows from nothing at the centre to amount pixels at the edges
+    vec2 offset = (uv - 0.5) * 2.0 * amount / resolution;
+    vec4 color = texture2D(Texture, uv);
+    vec4 red = texture2D(Texture, uv + offset);
+    vec4 blue = texture2D(Texture, uv - offset);
+
+    gl_FragColor = vec4(red.r, color.g, blue.b, max(color.a, max(red.a, blue.a)));
+}"#;
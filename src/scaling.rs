@@ -0,0 +1,162 @@
+use macroquad::prelude::*;
+
+// How the frame is scaled to the window when their sizes don't match
+#[derive(Default, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ScaleMode {
+    // Largest size that shows the whole frame, letterboxed on the sides that don't fill the window
+    #[default]
+    Fit,
+    // Smallest size that covers the whole window, the sides of the frame that overflow are cropped
+    Fill,
+    // Covers the whole window exactly, distorting the aspect ratio
+    Stretch,
+    // Largest whole number scale of the rendered pixels that fits, at least 1x so small windows
+    // crop instead
+    Integer,
+    // One rendered pixel per window pixel, centred
+    Fixed,
+}
+
+impl ScaleMode {
+    // The window rect the frame is drawn into, can extend past the window when cropping. The
+    // pixel size is the size the frame is rendered at, the pixel exact modes scale it instead of
+    // the logical frame size.
+    pub fn frame_rect(&self, frame_size: Vec2, pixel_size: Vec2, screen_size: Vec2) -> Rect {
+        let fit = |size: Vec2| f32::min(screen_size.x / size.x, screen_size.y / size.y);
+        let size = match self {
+            ScaleMode::Fit => frame_size * fit(frame_size),
+            ScaleMode::Fill => {
+                frame_size * f32::max(screen_size.x / frame_size.x, screen_size.y / frame_size.y)
+            }
+            ScaleMode::Stretch => screen_size,
+            ScaleMode::Integer => pixel_size * fit(pixel_size).floor().max(1.),
+            ScaleMode::Fixed => pixel_size,
+        };
+        let mut pos = (screen_size - size) * 0.5;
+        if matches!(self, ScaleMode::Integer | ScaleMode::Fixed) {
+            // Whole pixel offsets so every frame pixel lands exactly on window pixels
+            pos = pos.floor();
+        }
+
+        Rect::new(pos.x, pos.y, size.x, size.y)
+    }
+}
+
+// What fills the parts of the window not covered by the frame
+#[derive(Default, Clone)]
+pub enum Letterbox {
+    // The background colour of the animation
+    #[default]
+    Background,
+    Color(Color),
+    // Scaled to cover the whole window, cropping the image if the aspect ratios differ
+    Image(Texture2D),
+}
+
+impl Letterbox {
+    pub(crate) fn draw(&self, bg_color: Color) {
+        match self {
+            Letterbox::Background => clear_background(bg_color),
+            Letterbox::Color(color) => clear_background(*color),
+            Letterbox::Image(texture) => {
+                clear_background(bg_color);
+                let rect = ScaleMode::Fill.frame_rect(
+                    texture.size(),
+                    texture.size(),
+                    vec2(screen_width(), screen_height()),
+                );
+                draw_texture_ex(
+                    texture,
+                    rect.x,
+                    rect.y,
+                    WHITE,
+                    DrawTextureParams {
+                        dest_size: Some(rect.size()),
+                        ..Default::default()
+                    },
+                );
+            }
+        }
+    }
+}
+
+// The pixel size the scene is rendered at. World coordinates always span the logical size of the
+// animation, only the detail they are rendered with changes.
+#[derive(Default, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum RenderResolution {
+    // One pixel per logical unit
+    #[default]
+    Logical,
+    // e.g. 3840x2160 for exporting a 1280x720 animation in 4k
+    Fixed {
+        width: u32,
+        height: u32,
+    },
+    // Follows the size the frame is drawn at in the window, sharp at any window size
+    Window,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: Vec2 = vec2(160., 90.);
+
+    #[test]
+    fn fit_and_fill_keep_the_aspect_ratio() {
+        let screen = vec2(400., 400.);
+        assert_eq!(
+            ScaleMode::Fit.frame_rect(FRAME, FRAME, screen),
+            Rect::new(0., 87.5, 400., 225.)
+        );
+        let fill = ScaleMode::Fill.frame_rect(FRAME, FRAME, screen);
+        assert!(fill.size().abs_diff_eq(vec2(400. * 16. / 9., 400.), 1e-3));
+        assert!(fill.center().abs_diff_eq(screen / 2., 1e-3));
+        assert_eq!(
+            ScaleMode::Stretch.frame_rect(FRAME, FRAME, screen),
+            Rect::new(0., 0., 400., 400.)
+        );
+    }
+
+    #[test]
+    fn pixel_exact_modes_land_on_whole_pixels() {
+        let screen = vec2(500., 301.);
+        assert_eq!(
+            ScaleMode::Integer.frame_rect(FRAME, FRAME, screen),
+            Rect::new(10., 15., 480., 270.)
+        );
+        assert_eq!(
+            ScaleMode::Fixed.frame_rect(FRAME, FRAME, screen),
+            Rect::new(170., 105., 160., 90.)
+        );
+        // Windows smaller than the frame crop it
+        assert_eq!(
+            ScaleMode::Integer.frame_rect(FRAME, FRAME, vec2(100., 50.)),
+            Rect::new(-30., -20., 160., 90.)
+        );
+    }
+
+    #[test]
+    fn pixel_exact_modes_scale_the_render_resolution() {
+        // Rendered at three times the logical size
+        let pixels = FRAME * 3.;
+        let screen = vec2(1000., 600.);
+        assert_eq!(
+            ScaleMode::Integer.frame_rect(FRAME, pixels, screen),
+            Rect::new(20., 30., 960., 540.)
+        );
+        assert_eq!(
+            ScaleMode::Integer.frame_rect(FRAME, pixels, screen * 2.),
+            Rect::new(40., 60., 1920., 1080.)
+        );
+        assert_eq!(
+            ScaleMode::Fixed.frame_rect(FRAME, pixels, screen),
+            Rect::new(260., 165., 480., 270.)
+        );
+        // The other modes only care about the logical size
+        assert_eq!(
+            ScaleMode::Fit.frame_rect(FRAME, pixels, screen),
+            ScaleMode::Fit.frame_rect(FRAME, FRAME, screen)
+        );
+    }
+}
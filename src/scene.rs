@@ -0,0 +1,291 @@
+use macroquad::prelude::*;
+
+use crate::{error::Error, export::Export, Animation};
+
+pub fn window_conf(title: &str, width: f32, height: f32) -> Conf {
+    Conf {
+        window_title: title.to_owned(),
+        sample_count: 16,
+        window_width: width as i32,
+        window_height: height as i32,
+        ..Default::default()
+    }
+}
+
+pub trait Scene {
+    fn name(&self) -> &str;
+
+    // Called every time the scene starts playing, so it should reset any state
+    fn setup(&mut self, _animation: &mut Animation) {}
+
+    fn update(&mut self, animation: &Animation, dt: f32);
+
+    // Called in world space after Animation::set_camera
+    fn draw(&self, animation: &Animation);
+
+    // Scenes without a duration play until finished returns true
+    fn duration(&self) -> Option<f32> {
+        None
+    }
+
+    fn finished(&self) -> bool {
+        false
+    }
+}
+
+#[derive(Default, Copy, Clone)]
+pub enum Transition {
+    #[default]
+    Cut,
+    // Fades out to the background colour at the end of a scene and back in at the start of the next
+    Fade {
+        duration: f32,
+    },
+}
+
+// The command line arguments of a scene runner binary, see USAGE
+#[derive(Default)]
+pub struct SceneArgs {
+    pub scenes: Vec<String>,
+    pub export: Option<Export>,
+    // The binary should print the scene names instead of playing them
+    pub list: bool,
+}
+
+impl SceneArgs {
+    // Reads the arguments the program was started with
+    pub fn parse() -> Result<Self, Error> {
+        Self::from_args(std::env::args().skip(1))
+    }
+
+    // Fails with Error::Help when --help is given and Error::Usage for arguments it can't read
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Self, Error> {
+        let mut parsed = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| Error::Usage(format!("{arg} is missing its value")))
+            };
+            match arg.as_str() {
+                "--scene" => parsed.scenes.push(value()?),
+                "--export" => parsed.export = Some(Export::png_sequence(value()?)),
+                "--gif" => parsed.export = Some(Export::gif(value()?)),
+                "--list" => parsed.list = true,
+                "--help" => return Err(Error::Help),
+                _ => return Err(Error::Usage(format!("Unknown argument {arg}"))),
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+pub const USAGE: &str = "Options:
+    --scene <name>   play only this scene, can be given multiple times
+    --export <dir>   export the played scenes as a png sequence
+    --gif <path>     export the played scenes as a gif
+    --list           print the scene names and exit
+    --help           print this message and exit";
+
+#[derive(Default)]
+pub struct SceneRunner {
+    scenes: Vec<Box<dyn Scene>>,
+    transition: Transition,
+    selected: Vec<String>,
+    export: Option<Export>,
+}
+
+impl SceneRunner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn scene(mut self, scene: impl Scene + 'static) -> Self {
+        self.scenes.push(Box::new(scene));
+        self
+    }
+
+    pub fn transition(mut self, transition: Transition) -> Self {
+        self.transition = transition;
+        self
+    }
+
+    // Only play the scenes with the given names. Plays everything if nothing is selected.
+    pub fn select(mut self, name: &str) -> Self {
+        self.selected.push(name.to_owned());
+        self
+    }
+
+    pub fn export(mut self, export: Export) -> Self {
+        self.export = Some(export);
+        self
+    }
+
+    // Selects the scenes and export given on the command line
+    pub fn args(mut self, args: SceneArgs) -> Self {
+        self.selected.extend(args.scenes);
+        if args.export.is_some() {
+            self.export = args.export;
+        }
+        self
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.scenes.iter().map(|scene| scene.name()).collect()
+    }
+
+    // Plays the selected scenes in order. An export given to the runner is finished after the
+    // last scene, one started on the animation beforehand is left running.
+    pub async fn run(mut self, animation: &mut Animation) -> Result<(), Error> {
+        if let Some(name) = self.selected.iter().find(|name| {
+            !self
+                .scenes
+                .iter()
+                .any(|scene| scene.name() == name.as_str())
+        }) {
+            return Err(Error::UnknownScene(name.clone()));
+        }
+
+        let exporting = self.export.is_some();
+        if let Some(export) = self.export.take() {
+            animation.start_export(export)?;
+        }
+
+        let selected = std::mem::take(&mut self.selected);
+        let mut result = Ok(());
+        for scene in self
+            .scenes
+            .iter_mut()
+            .filter(|scene| selected.is_empty() || selected.iter().any(|name| name == scene.name()))
+        {
+            result = play_scene(scene.as_mut(), self.transition, animation).await;
+            if result.is_err() {
+                break;
+            }
+        }
+
+        // Writes out what was exported even when a scene failed
+        if exporting {
+            animation.stop_export()?;
+        }
+        result
+    }
+}
+
+struct PlayingScene<'a> {
+    scene: &'a mut dyn Scene,
+    finished_at: Option<f32>,
+    // Opacity of the background drawn over the scene
+    fade: f32,
+}
+
+async fn play_scene(
+    scene: &mut dyn Scene,
+    transition: Transition,
+    animation: &mut Animation,
+) -> Result<(), Error> {
+    scene.setup(animation);
+
+    let fade_duration = match transition {
+        Transition::Cut => 0.,
+        Transition::Fade { duration } => duration,
+    };
+    let mut playing = PlayingScene {
+        scene,
+        finished_at: None,
+        fade: 0.,
+    };
+
+    animation
+        .run_frames(
+            &mut playing,
+            |playing, animation, elapsed| {
+                if playing.finished_at.is_none()
+                    && (playing.scene.finished()
+                        || matches!(playing.scene.duration(), Some(duration) if elapsed >= duration))
+                {
+                    playing.finished_at = Some(elapsed);
+                }
+
+                playing.scene.update(animation, animation.dt());
+
+                if fade_duration > 0. {
+                    let fade_in = 1. - elapsed / fade_duration;
+                    let fade_out = playing
+                        .finished_at
+                        .map_or(0., |finished_at| (elapsed - finished_at) / fade_duration);
+                    playing.fade = f32::max(fade_in, fade_out).clamp(0., 1.);
+                }
+
+                // Let the scene keep playing while it fades out
+                !matches!(playing.finished_at, Some(finished_at) if elapsed - finished_at >= fade_duration)
+            },
+            |playing, animation| draw_faded(playing.scene, animation, playing.fade),
+        )
+        .await
+}
+
+fn draw_faded(scene: &dyn Scene, animation: &Animation, fade: f32) {
+    scene.draw(animation);
+    if fade <= 0. {
+        return;
+    }
+
+    // Covers everything the frame and the insets can show, whatever the camera rotation
+    let visible = animation.visible_world_rect();
+    let covered = animation
+        .insets
+        .names()
+        .iter()
+        .filter_map(|name| animation.inset(name))
+        .fold(
+            Rect::new(
+                visible.x - visible.w / 2.,
+                visible.y - visible.h / 2.,
+                visible.w * 2.,
+                visible.h * 2.,
+            ),
+            |covered, inset| covered.combine_with(inset.source()),
+        );
+    draw_rectangle(
+        covered.x,
+        covered.y,
+        covered.w,
+        covered.h,
+        Color {
+            a: fade,
+            ..animation.bg_color()
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<SceneArgs, Error> {
+        SceneArgs::from_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn args_select_scenes_and_exports() {
+        let args = parse(&["--scene", "intro", "--list", "--scene", "outro"]).unwrap();
+        assert_eq!(args.scenes, ["intro", "outro"]);
+        assert!(args.list);
+        assert!(args.export.is_none());
+        assert!(parse(&["--gif", "out.gif"]).unwrap().export.is_some());
+    }
+
+    #[test]
+    fn bad_args_are_errors() {
+        assert!(matches!(parse(&["--help"]), Err(Error::Help)));
+        assert!(matches!(
+            parse(&["--scnee", "intro"]),
+            Err(Error::Usage(message)) if message.contains("--scnee")
+        ));
+        assert!(matches!(
+            parse(&["--scene"]),
+            Err(Error::Usage(message)) if message.contains("--scene")
+        ));
+    }
+}
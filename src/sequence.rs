@@ -0,0 +1,275 @@
+use std::{
+    cell::{Ref, RefCell, RefMut},
+    rc::Rc,
+};
+
+use macroquad::prelude::*;
+
+use crate::{easing::Easing, tween::Lerp};
+
+const PROGRESS_EPSILON: f32 = 1e-4;
+
+// A value shared between the scene's draw function and the actions that animate it
+pub struct Property<T>(Rc<RefCell<T>>);
+
+impl<T> Clone for Property<T> {
+    fn clone(&self) -> Self {
+        Self(Rc::clone(&self.0))
+    }
+}
+
+impl<T> Property<T> {
+    pub fn new(value: T) -> Self {
+        Self(Rc::new(RefCell::new(value)))
+    }
+
+    pub fn borrow(&self) -> Ref<'_, T> {
+        self.0.borrow()
+    }
+
+    pub fn borrow_mut(&self) -> RefMut<'_, T> {
+        self.0.borrow_mut()
+    }
+
+    pub fn set(&self, value: T) {
+        *self.0.borrow_mut() = value;
+    }
+}
+
+impl<T: Clone> Property<T> {
+    pub fn get(&self) -> T {
+        self.0.borrow().clone()
+    }
+}
+
+pub trait Opacity {
+    fn opacity(&self) -> f32;
+    fn set_opacity(&mut self, opacity: f32);
+}
+
+impl Opacity for f32 {
+    fn opacity(&self) -> f32 {
+        *self
+    }
+
+    fn set_opacity(&mut self, opacity: f32) {
+        *self = opacity;
+    }
+}
+
+impl Opacity for Color {
+    fn opacity(&self) -> f32 {
+        self.a
+    }
+
+    fn set_opacity(&mut self, opacity: f32) {
+        self.a = opacity;
+    }
+}
+
+pub trait Action {
+    // Called once on the frame the action starts, before the first update
+    fn begin(&mut self) {}
+
+    // Progress goes from 0 to 1 over the duration of the action and always ends on exactly 1
+    fn update(&mut self, progress: f32);
+
+    fn boxed(self) -> Box<dyn Action>
+    where
+        Self: Sized + 'static,
+    {
+        Box::new(self)
+    }
+}
+
+impl<F: FnMut(f32)> Action for F {
+    fn update(&mut self, progress: f32) {
+        self(progress)
+    }
+}
+
+impl Action for Box<dyn Action> {
+    fn begin(&mut self) {
+        self.as_mut().begin()
+    }
+
+    fn update(&mut self, progress: f32) {
+        self.as_mut().update(progress)
+    }
+}
+
+// Animates a property from whatever value it has when the action begins to the target value
+pub struct TweenTo<T> {
+    property: Property<T>,
+    lerper: Option<Box<dyn Fn(f32) -> T>>,
+    to: T,
+    easing: Easing,
+}
+
+impl<T> TweenTo<T> {
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+}
+
+impl<T: Lerp + Clone + 'static> Action for TweenTo<T> {
+    fn begin(&mut self) {
+        self.lerper = Some(self.property.get().lerper(&self.to));
+    }
+
+    fn update(&mut self, progress: f32) {
+        let lerper = self
+            .lerper
+            .get_or_insert_with(|| self.property.get().lerper(&self.to));
+        self.property.set(lerper(self.easing.ease(progress)));
+    }
+}
+
+pub fn tween_to<T: Lerp + Clone>(property: &Property<T>, to: T) -> TweenTo<T> {
+    TweenTo {
+        property: property.clone(),
+        lerper: None,
+        to,
+        easing: Easing::SmoothStep,
+    }
+}
+
+pub struct Fade<T> {
+    target: Property<T>,
+    from: f32,
+    to: f32,
+    easing: Easing,
+}
+
+impl<T> Fade<T> {
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+}
+
+impl<T: Opacity> Action for Fade<T> {
+    fn begin(&mut self) {
+        self.target.borrow_mut().set_opacity(self.from);
+    }
+
+    fn update(&mut self, progress: f32) {
+        let opacity = self.from.lerp(&self.to, self.easing.ease(progress));
+        self.target.borrow_mut().set_opacity(opacity);
+    }
+}
+
+pub fn fade_in<T: Opacity>(target: &Property<T>) -> Fade<T> {
+    Fade {
+        target: target.clone(),
+        from: 0.,
+        to: 1.,
+        easing: Easing::SmoothStep,
+    }
+}
+
+pub fn fade_out<T: Opacity>(target: &Property<T>) -> Fade<T> {
+    Fade {
+        target: target.clone(),
+        from: 1.,
+        to: 0.,
+        easing: Easing::SmoothStep,
+    }
+}
+
+struct GroupEntry {
+    action: Box<dyn Action>,
+    start: f32,
+    end: f32,
+    started: bool,
+    finished: bool,
+}
+
+// Plays child actions over sub ranges of its own progress
+pub struct Group {
+    entries: Vec<GroupEntry>,
+}
+
+impl Group {
+    fn new(actions: Vec<Box<dyn Action>>, ranges: impl Iterator<Item = (f32, f32)>) -> Self {
+        let entries = actions
+            .into_iter()
+            .zip(ranges)
+            .map(|(action, (start, end))| GroupEntry {
+                action,
+                start,
+                end,
+                started: false,
+                finished: false,
+            })
+            .collect();
+
+        Self { entries }
+    }
+}
+
+impl Action for Group {
+    fn update(&mut self, progress: f32) {
+        for entry in &mut self.entries {
+            if entry.finished || progress < entry.start {
+                continue;
+            }
+
+            if !entry.started {
+                entry.started = true;
+                entry.action.begin();
+            }
+
+            let span = entry.end - entry.start;
+            let local = if span <= 0. {
+                1.
+            } else {
+                ((progress - entry.start) / span).clamp(0., 1.)
+            };
+            entry.action.update(local);
+            entry.finished = local >= 1.;
+        }
+    }
+}
+
+// Every action plays over the whole duration
+pub fn parallel(actions: Vec<Box<dyn Action>>) -> Group {
+    Group::new(actions, std::iter::repeat((0., 1.)))
+}
+
+// Actions play one after the other, each given an equal share of the duration
+pub fn sequence(actions: Vec<Box<dyn Action>>) -> Group {
+    let count = actions.len().max(1) as f32;
+    Group::new(
+        actions,
+        (0..).map(move |i| (i as f32 / count, (i + 1) as f32 / count)),
+    )
+}
+
+// Each action starts lag_ratio of an action's length after the previous one started.
+// A lag_ratio of 0 is the same as parallel and 1 is the same as sequence.
+pub fn lagged_start(actions: Vec<Box<dyn Action>>, lag_ratio: f32) -> Group {
+    let count = actions.len().max(1) as f32;
+    let length = 1. / (1. + lag_ratio * (count - 1.));
+    Group::new(
+        actions,
+        (0..).map(move |i| {
+            let start = i as f32 * lag_ratio * length;
+            (start, start + length)
+        }),
+    )
+}
+
+pub(crate) fn progress(elapsed: f32, duration: f32) -> f32 {
+    if duration <= 0. {
+        return 1.;
+    }
+
+    let progress = elapsed / duration;
+    if progress >= 1. - PROGRESS_EPSILON {
+        1.
+    } else {
+        progress.max(0.)
+    }
+}
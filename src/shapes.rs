@@ -0,0 +1,992 @@
+use macroquad::prelude::*;
+
+use crate::{
+    sequence::Opacity,
+    tessellate::{draw_polyline, meshes, DashPattern, LineCap, LineJoin, StrokeStyle},
+    tween::Lerp,
+    Animation,
+};
+
+const CIRCLE_SEGMENTS: usize = 96;
+// Default sizes in logical pixels, used as world units unless the animation converts them
+const DEFAULT_STROKE_PIXELS: f32 = 2.;
+const DEFAULT_HEAD_PIXELS: Vec2 = vec2(16., 12.);
+
+// Places a shape's local coordinates in the world: scaled, rotated about the local origin and then
+// moved to the position
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transform {
+    pub position: Vec2,
+    pub rotation: f32, // Degrees counter clockwise
+    pub scale: Vec2,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self {
+            position: vec2(0., 0.),
+            rotation: 0.,
+            scale: vec2(1., 1.),
+        }
+    }
+}
+
+impl Transform {
+    pub fn new(position: Vec2) -> Self {
+        Self {
+            position,
+            ..Default::default()
+        }
+    }
+
+    pub fn apply(&self, point: Vec2) -> Vec2 {
+        let rotation = Vec2::from_angle(self.rotation.to_radians());
+        self.position + rotation.rotate(point * self.scale)
+    }
+}
+
+impl Lerp for Transform {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self {
+            position: self.position.lerp(other.position, t),
+            rotation: self.rotation.lerp(&other.rotation, t),
+            scale: self.scale.lerp(other.scale, t),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShapeStyle {
+    pub fill: Option<Color>,
+    pub stroke: Option<Color>,
+    // World units, unaffected by the transform's scale. Defaults to 2, for_animation makes it 2
+    // logical pixels.
+    pub stroke_width: f32,
+    pub join: LineJoin,
+    pub cap: LineCap, // Only open outlines and dashes have caps
+    pub dash: Option<DashPattern>,
+    // Multiplies the alpha of the fill and the stroke
+    pub opacity: f32,
+}
+
+impl Default for ShapeStyle {
+    fn default() -> Self {
+        Self::with_units_per_pixel(1.)
+    }
+}
+
+impl ShapeStyle {
+    // The default style with the stroke width in logical pixels of the animation's world
+    pub fn for_animation(animation: &Animation) -> Self {
+        Self::with_units_per_pixel(animation.units_per_pixel())
+    }
+
+    fn with_units_per_pixel(units_per_pixel: f32) -> Self {
+        Self {
+            fill: Some(WHITE),
+            stroke: None,
+            stroke_width: DEFAULT_STROKE_PIXELS * units_per_pixel,
+            join: LineJoin::default(),
+            cap: LineCap::default(),
+            dash: None,
+            opacity: 1.,
+        }
+    }
+
+    pub fn stroke_style(&self) -> StrokeStyle {
+        StrokeStyle {
+            dash: self.dash.clone(),
+            ..StrokeStyle::new(self.stroke_width)
+                .join(self.join)
+                .cap(self.cap)
+        }
+    }
+}
+
+impl Lerp for ShapeStyle {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self {
+            fill: lerp_paint(self.fill, other.fill, t),
+            stroke: lerp_paint(self.stroke, other.stroke, t),
+            stroke_width: self.stroke_width.lerp(&other.stroke_width, t),
+            // Joins and caps can't be blended, they switch halfway
+            join: if t < 0.5 { self.join } else { other.join },
+            cap: if t < 0.5 { self.cap } else { other.cap },
+            dash: match (&self.dash, &other.dash) {
+                (Some(from), Some(to)) => Some(from.lerp(to, t)),
+                _ if t < 0.5 => self.dash.clone(),
+                _ => other.dash.clone(),
+            },
+            opacity: self.opacity.lerp(&other.opacity, t),
+        }
+    }
+}
+
+// A missing fill or stroke fades in or out from the colour on the other side
+fn lerp_paint(from: Option<Color>, to: Option<Color>, t: f32) -> Option<Color> {
+    let transparent = |color: Color| Color { a: 0., ..color };
+    match (from, to) {
+        (Some(from), Some(to)) => Some(from.lerp(&to, t)),
+        (Some(from), None) => Some(from.lerp(&transparent(from), t)),
+        (None, Some(to)) => Some(transparent(to).lerp(&to, t)),
+        (None, None) => None,
+    }
+}
+
+// A retained shape drawn in world coordinates, after Animation::set_camera. Shapes are plain values
+// so they can be stored in a Property and animated with tween_to and fade_in/fade_out.
+pub trait Shape {
+    fn transform(&self) -> &Transform;
+    fn transform_mut(&mut self) -> &mut Transform;
+    fn style(&self) -> &ShapeStyle;
+    fn style_mut(&mut self) -> &mut ShapeStyle;
+
+    // The outline in local coordinates, before the transform
+    fn local_outline(&self) -> Vec<Vec2>;
+
+    // Open shapes are only stroked
+    fn is_closed(&self) -> bool {
+        true
+    }
+
+    // The outline in world coordinates
+    fn outline(&self) -> Vec<Vec2> {
+        let transform = self.transform();
+        self.local_outline()
+            .into_iter()
+            .map(|point| transform.apply(point))
+            .collect()
+    }
+
+    fn draw(&self) {
+        draw_outline(&self.outline(), self.is_closed(), self.style());
+    }
+
+    fn position(mut self, position: Vec2) -> Self
+    where
+        Self: Sized,
+    {
+        self.transform_mut().position = position;
+        self
+    }
+
+    fn rotation(mut self, rotation: f32) -> Self
+    where
+        Self: Sized,
+    {
+        self.transform_mut().rotation = rotation;
+        self
+    }
+
+    fn scale(mut self, scale: Vec2) -> Self
+    where
+        Self: Sized,
+    {
+        self.transform_mut().scale = scale;
+        self
+    }
+
+    fn fill(mut self, color: Color) -> Self
+    where
+        Self: Sized,
+    {
+        self.style_mut().fill = Some(color);
+        self
+    }
+
+    fn no_fill(mut self) -> Self
+    where
+        Self: Sized,
+    {
+        self.style_mut().fill = None;
+        self
+    }
+
+    fn stroke(mut self, color: Color, width: f32) -> Self
+    where
+        Self: Sized,
+    {
+        let style = self.style_mut();
+        style.stroke = Some(color);
+        style.stroke_width = width;
+        self
+    }
+
+    fn dash(mut self, dash: DashPattern) -> Self
+    where
+        Self: Sized,
+    {
+        self.style_mut().dash = Some(dash);
+        self
+    }
+}
+
+impl<T: Shape> Opacity for T {
+    fn opacity(&self) -> f32 {
+        self.style().opacity
+    }
+
+    fn set_opacity(&mut self, opacity: f32) {
+        self.style_mut().opacity = opacity;
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Circle {
+    radius: f32,
+    transform: Transform,
+    style: ShapeStyle,
+}
+
+impl Circle {
+    pub fn new(center: Vec2, radius: f32) -> Self {
+        Self {
+            radius,
+            transform: Transform::new(center),
+            style: ShapeStyle::default(),
+        }
+    }
+
+    pub fn radius(&self) -> f32 {
+        self.radius
+    }
+
+    pub fn set_radius(&mut self, radius: f32) {
+        self.radius = radius;
+    }
+}
+
+impl Shape for Circle {
+    fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn transform_mut(&mut self) -> &mut Transform {
+        &mut self.transform
+    }
+
+    fn style(&self) -> &ShapeStyle {
+        &self.style
+    }
+
+    fn style_mut(&mut self) -> &mut ShapeStyle {
+        &mut self.style
+    }
+
+    fn local_outline(&self) -> Vec<Vec2> {
+        (0..CIRCLE_SEGMENTS)
+            .map(|index| {
+                let angle = index as f32 / CIRCLE_SEGMENTS as f32 * std::f32::consts::TAU;
+                Vec2::from_angle(angle) * self.radius
+            })
+            .collect()
+    }
+}
+
+impl Lerp for Circle {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self {
+            radius: self.radius.lerp(&other.radius, t),
+            transform: self.transform.lerp(&other.transform, t),
+            style: self.style.lerp(&other.style, t),
+        }
+    }
+}
+
+// Centred on its position
+#[derive(Clone, Debug)]
+pub struct Rectangle {
+    size: Vec2,
+    transform: Transform,
+    style: ShapeStyle,
+}
+
+impl Rectangle {
+    pub fn new(center: Vec2, size: Vec2) -> Self {
+        Self {
+            size,
+            transform: Transform::new(center),
+            style: ShapeStyle::default(),
+        }
+    }
+
+    pub fn size(&self) -> Vec2 {
+        self.size
+    }
+
+    pub fn set_size(&mut self, size: Vec2) {
+        self.size = size;
+    }
+}
+
+impl Shape for Rectangle {
+    fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn transform_mut(&mut self) -> &mut Transform {
+        &mut self.transform
+    }
+
+    fn style(&self) -> &ShapeStyle {
+        &self.style
+    }
+
+    fn style_mut(&mut self) -> &mut ShapeStyle {
+        &mut self.style
+    }
+
+    fn local_outline(&self) -> Vec<Vec2> {
+        let half = self.size / 2.;
+        vec![
+            vec2(-half.x, -half.y),
+            vec2(half.x, -half.y),
+            vec2(half.x, half.y),
+            vec2(-half.x, half.y),
+        ]
+    }
+}
+
+impl Lerp for Rectangle {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self {
+            size: self.size.lerp(other.size, t),
+            transform: self.transform.lerp(&other.transform, t),
+            style: self.style.lerp(&other.style, t),
+        }
+    }
+}
+
+// Any simple polygon, concave polygons are filled correctly
+#[derive(Clone, Debug)]
+pub struct Polygon {
+    points: Vec<Vec2>,
+    transform: Transform,
+    style: ShapeStyle,
+}
+
+impl Polygon {
+    // The points are in local coordinates, relative to the position
+    pub fn new(points: Vec<Vec2>) -> Self {
+        Self {
+            points,
+            transform: Transform::default(),
+            style: ShapeStyle::default(),
+        }
+    }
+
+    // The first corner points straight up
+    pub fn regular(center: Vec2, radius: f32, sides: usize) -> Self {
+        let points = (0..sides)
+            .map(|index| {
+                let angle = std::f32::consts::FRAC_PI_2
+                    + index as f32 / sides as f32 * std::f32::consts::TAU;
+                Vec2::from_angle(angle) * radius
+            })
+            .collect();
+        Self::new(points).position(center)
+    }
+
+    pub fn points(&self) -> &[Vec2] {
+        &self.points
+    }
+
+    pub fn set_points(&mut self, points: Vec<Vec2>) {
+        self.points = points;
+    }
+}
+
+impl Shape for Polygon {
+    fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn transform_mut(&mut self) -> &mut Transform {
+        &mut self.transform
+    }
+
+    fn style(&self) -> &ShapeStyle {
+        &self.style
+    }
+
+    fn style_mut(&mut self) -> &mut ShapeStyle {
+        &mut self.style
+    }
+
+    fn local_outline(&self) -> Vec<Vec2> {
+        self.points.clone()
+    }
+}
+
+impl Lerp for Polygon {
+    // Polygons with different numbers of points swap over halfway instead of moving their points
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        let points = if self.points.len() == other.points.len() {
+            self.points
+                .iter()
+                .zip(&other.points)
+                .map(|(from, to)| from.lerp(to, t))
+                .collect()
+        } else if t < 0.5 {
+            self.points.clone()
+        } else {
+            other.points.clone()
+        };
+
+        Self {
+            points,
+            transform: self.transform.lerp(&other.transform, t),
+            style: self.style.lerp(&other.style, t),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Line {
+    start: Vec2,
+    end: Vec2,
+    transform: Transform,
+    style: ShapeStyle,
+}
+
+impl Line {
+    pub fn new(start: Vec2, end: Vec2) -> Self {
+        Self {
+            start,
+            end,
+            transform: Transform::default(),
+            style: ShapeStyle {
+                fill: None,
+                stroke: Some(WHITE),
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn start(&self) -> Vec2 {
+        self.start
+    }
+
+    pub fn end(&self) -> Vec2 {
+        self.end
+    }
+
+    pub fn set_points(&mut self, start: Vec2, end: Vec2) {
+        self.start = start;
+        self.end = end;
+    }
+}
+
+impl Shape for Line {
+    fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn transform_mut(&mut self) -> &mut Transform {
+        &mut self.transform
+    }
+
+    fn style(&self) -> &ShapeStyle {
+        &self.style
+    }
+
+    fn style_mut(&mut self) -> &mut ShapeStyle {
+        &mut self.style
+    }
+
+    fn local_outline(&self) -> Vec<Vec2> {
+        vec![self.start, self.end]
+    }
+
+    fn is_closed(&self) -> bool {
+        false
+    }
+}
+
+impl Lerp for Line {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self {
+            start: self.start.lerp(other.start, t),
+            end: self.end.lerp(other.end, t),
+            transform: self.transform.lerp(&other.transform, t),
+            style: self.style.lerp(&other.style, t),
+        }
+    }
+}
+
+// A line with a triangular head at the end, drawn in the stroke colour
+#[derive(Clone, Debug)]
+pub struct Arrow {
+    start: Vec2,
+    end: Vec2,
+    head_length: f32,
+    head_width: f32,
+    transform: Transform,
+    style: ShapeStyle,
+}
+
+impl Arrow {
+    pub fn new(start: Vec2, end: Vec2) -> Self {
+        Self::with_units_per_pixel(start, end, 1.)
+    }
+
+    // An arrow with the head and stroke sized in logical pixels of the animation's world
+    pub fn for_animation(start: Vec2, end: Vec2, animation: &Animation) -> Self {
+        Self::with_units_per_pixel(start, end, animation.units_per_pixel())
+    }
+
+    fn with_units_per_pixel(start: Vec2, end: Vec2, units_per_pixel: f32) -> Self {
+        Self {
+            start,
+            end,
+            head_length: DEFAULT_HEAD_PIXELS.x * units_per_pixel,
+            head_width: DEFAULT_HEAD_PIXELS.y * units_per_pixel,
+            transform: Transform::default(),
+            style: ShapeStyle {
+                fill: None,
+                stroke: Some(WHITE),
+                ..ShapeStyle::with_units_per_pixel(units_per_pixel)
+            },
+        }
+    }
+
+    // In local units, the head shrinks to fit arrows shorter than it. Defaults to 16 by 12.
+    pub fn head(mut self, length: f32, width: f32) -> Self {
+        self.head_length = length;
+        self.head_width = width;
+        self
+    }
+
+    pub fn start(&self) -> Vec2 {
+        self.start
+    }
+
+    pub fn end(&self) -> Vec2 {
+        self.end
+    }
+
+    pub fn set_points(&mut self, start: Vec2, end: Vec2) {
+        self.start = start;
+        self.end = end;
+    }
+
+    // The base of the head and its length, shortened for short arrows
+    fn head_base(&self) -> (Vec2, Vec2, f32) {
+        let direction = (self.end - self.start).normalize_or_zero();
+        let length = self.head_length.min(self.start.distance(self.end));
+        let scale = if self.head_length > 0. {
+            length / self.head_length
+        } else {
+            0.
+        };
+        (
+            self.end - direction * length,
+            direction.perp() * self.head_width * scale / 2.,
+            length,
+        )
+    }
+}
+
+impl Shape for Arrow {
+    fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn transform_mut(&mut self) -> &mut Transform {
+        &mut self.transform
+    }
+
+    fn style(&self) -> &ShapeStyle {
+        &self.style
+    }
+
+    fn style_mut(&mut self) -> &mut ShapeStyle {
+        &mut self.style
+    }
+
+    // The shaft and the head as one outline, from the start to the tip and back
+    fn local_outline(&self) -> Vec<Vec2> {
+        let (base, side, _) = self.head_base();
+        vec![self.start, base, base + side, self.end, base - side, base]
+    }
+
+    fn is_closed(&self) -> bool {
+        false
+    }
+
+    fn draw(&self) {
+        let Some(stroke) = self.style.stroke else {
+            return;
+        };
+        let (base, side, length) = self.head_base();
+        let points = [self.start, base, base + side, self.end, base - side]
+            .map(|point| self.transform.apply(point));
+
+        draw_outline(
+            &points[..2],
+            false,
+            &ShapeStyle {
+                fill: None,
+                ..self.style.clone()
+            },
+        );
+        if length > 0. {
+            fill_polygon(&points[2..], with_opacity(stroke, self.style.opacity));
+        }
+    }
+}
+
+impl Lerp for Arrow {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self {
+            start: self.start.lerp(other.start, t),
+            end: self.end.lerp(other.end, t),
+            head_length: self.head_length.lerp(&other.head_length, t),
+            head_width: self.head_width.lerp(&other.head_width, t),
+            transform: self.transform.lerp(&other.transform, t),
+            style: self.style.lerp(&other.style, t),
+        }
+    }
+}
+
+pub(crate) fn with_opacity(color: Color, opacity: f32) -> Color {
+    Color {
+        a: color.a * opacity,
+        ..color
+    }
+}
+
+// Fills and strokes an outline in world coordinates
+pub(crate) fn draw_outline(points: &[Vec2], closed: bool, style: &ShapeStyle) {
+    if let (Some(fill), true) = (style.fill, closed) {
+        fill_polygon(points, with_opacity(fill, style.opacity));
+    }
+
+    if let Some(stroke) = style.stroke {
+        draw_polyline(
+            points,
+            closed,
+            &style.stroke_style(),
+            with_opacity(stroke, style.opacity),
+        );
+    }
+}
+
+pub(crate) fn fill_polygon(points: &[Vec2], color: Color) {
+    // Long outlines, like filled paths and glyphs, take more than one draw call
+    for mesh in meshes(points, &triangulate(points), color) {
+        draw_mesh(&mesh);
+    }
+}
+
+// Fills closed contours with the even-odd rule, so a contour inside another one is a hole, like the
+// inside of an o
+pub(crate) fn fill_contours(contours: &[&[Vec2]], color: Color) {
+    for polygon in contour_polygons(contours) {
+        fill_polygon(&polygon, color);
+    }
+}
+
+// The outer contours with the holes directly inside them bridged in
+fn contour_polygons(contours: &[&[Vec2]]) -> Vec<Vec<Vec2>> {
+    let contours: Vec<&[Vec2]> = contours
+        .iter()
+        .copied()
+        .filter(|contour| contour.len() > 2)
+        .collect();
+    let containers = |index: usize| -> Vec<usize> {
+        (0..contours.len())
+            .filter(|&other| other != index && contains(contours[other], contours[index][0]))
+            .collect()
+    };
+    let depths: Vec<usize> = (0..contours.len())
+        .map(|index| containers(index).len())
+        .collect();
+
+    let mut polygons = Vec::new();
+    for (index, outer) in contours.iter().enumerate() {
+        if depths[index] % 2 == 1 {
+            continue;
+        }
+
+        // The holes directly inside this contour
+        let mut holes: Vec<Vec<Vec2>> = (0..contours.len())
+            .filter(|&hole| depths[hole] == depths[index] + 1 && containers(hole).contains(&index))
+            .map(|hole| oriented(contours[hole], false))
+            .collect();
+        // Bridging the rightmost hole first keeps the bridges from crossing
+        holes.sort_by(|a, b| max_x(b).total_cmp(&max_x(a)));
+
+        let mut points = oriented(outer, true);
+        for hole in holes {
+            points = bridge(&points, &hole);
+        }
+        polygons.push(points);
+    }
+    polygons
+}
+
+// Positive for counter clockwise polygons
+pub(crate) fn signed_area(points: &[Vec2]) -> f32 {
+    (0..points.len())
+        .map(|index| points[index].perp_dot(points[(index + 1) % points.len()]))
+        .sum::<f32>()
+        / 2.
+}
+
+fn oriented(points: &[Vec2], counter_clockwise: bool) -> Vec<Vec2> {
+    let mut points = points.to_vec();
+    if (signed_area(&points) > 0.) != counter_clockwise {
+        points.reverse();
+    }
+    points
+}
+
+fn max_x(points: &[Vec2]) -> f32 {
+    points.iter().map(|point| point.x).fold(f32::MIN, f32::max)
+}
+
+// Even-odd point in polygon test
+fn contains(polygon: &[Vec2], point: Vec2) -> bool {
+    let mut inside = false;
+    for index in 0..polygon.len() {
+        let (a, b) = (polygon[index], polygon[(index + 1) % polygon.len()]);
+        if (a.y > point.y) != (b.y > point.y)
+            && point.x < a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x)
+        {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+// Cuts a hole into a polygon by joining them with a bridge there and back, from the hole's
+// rightmost point to the nearest point of the polygon it can see
+fn bridge(polygon: &[Vec2], hole: &[Vec2]) -> Vec<Vec2> {
+    let Some(hole_index) = (0..hole.len()).max_by(|&a, &b| hole[a].x.total_cmp(&hole[b].x)) else {
+        return polygon.to_vec();
+    };
+    let from = hole[hole_index];
+
+    let edges = |points: &[Vec2]| -> Vec<(Vec2, Vec2)> {
+        (0..points.len())
+            .map(|index| (points[index], points[(index + 1) % points.len()]))
+            .collect()
+    };
+    let all_edges: Vec<(Vec2, Vec2)> = edges(polygon).into_iter().chain(edges(hole)).collect();
+
+    let mut candidates: Vec<usize> = (0..polygon.len()).collect();
+    candidates.sort_by(|&a, &b| {
+        polygon[a]
+            .distance_squared(from)
+            .total_cmp(&polygon[b].distance_squared(from))
+    });
+    let visible = candidates.iter().copied().find(|&index| {
+        let to = polygon[index];
+        let middle = (from + to) / 2.;
+        contains(polygon, middle)
+            && !contains(hole, middle)
+            && all_edges.iter().all(|&(a, b)| !crosses(from, to, a, b))
+    });
+    let polygon_index = visible.unwrap_or(candidates[0]);
+
+    let mut points = Vec::with_capacity(polygon.len() + hole.len() + 2);
+    points.extend_from_slice(&polygon[..=polygon_index]);
+    points.extend((0..=hole.len()).map(|offset| hole[(hole_index + offset) % hole.len()]));
+    points.extend_from_slice(&polygon[polygon_index..]);
+    points
+}
+
+// Whether two segments cross at a point inside both of them
+fn crosses(a: Vec2, b: Vec2, c: Vec2, d: Vec2) -> bool {
+    let side = |p: Vec2, q: Vec2, r: Vec2| (q - p).perp_dot(r - p);
+    let (abc, abd) = (side(a, b, c), side(a, b, d));
+    let (cda, cdb) = (side(c, d, a), side(c, d, b));
+    abc * abd < 0. && cda * cdb < 0.
+}
+
+// Triangulates a simple polygon by ear clipping, returning indices into the points. Works for
+// either winding and for concave polygons. Convex polygons are fanned, otherwise only the reflex
+// corners can be inside an ear so only they are checked.
+pub(crate) fn triangulate(points: &[Vec2]) -> Vec<u32> {
+    let count = points.len();
+    if count < 3 {
+        return Vec::new();
+    }
+
+    // Corners in counter clockwise order as a linked list
+    let forwards = signed_area(points) >= 0.;
+    let step = |index: usize, ahead: bool| {
+        if ahead == forwards {
+            (index + 1) % count
+        } else {
+            (index + count - 1) % count
+        }
+    };
+    let mut next: Vec<usize> = (0..count).map(|index| step(index, true)).collect();
+    let mut previous: Vec<usize> = (0..count).map(|index| step(index, false)).collect();
+    let convex = |previous: usize, current: usize, next: usize| {
+        (points[current] - points[previous]).perp_dot(points[next] - points[current]) > 0.
+    };
+
+    let mut reflex: Vec<usize> = (0..count)
+        .filter(|&index| !convex(previous[index], index, next[index]))
+        .collect();
+    let mut indices = Vec::with_capacity((count - 2) * 3);
+    if reflex.is_empty() {
+        fan(&mut indices, 0, &next, count);
+        return indices;
+    }
+
+    let mut current = 0;
+    let mut remaining = count;
+    // Corners looked at since the last ear was found
+    let mut misses = 0;
+    while remaining > 3 {
+        let (before, after) = (previous[current], next[current]);
+        let triangle = [points[before], points[current], points[after]];
+        let is_ear = convex(before, current, after)
+            && reflex.iter().all(|&other| {
+                let point = points[other];
+                triangle.contains(&point)
+                    || !in_triangle(point, triangle[0], triangle[1], triangle[2])
+            });
+
+        if !is_ear {
+            misses += 1;
+            // Degenerate polygons, e.g. with collinear or self intersecting edges, have no ear
+            // left. The rest is fanned so something is still drawn.
+            if misses > remaining {
+                fan(&mut indices, current, &next, remaining);
+                return indices;
+            }
+            current = after;
+            continue;
+        }
+
+        indices.extend([before, current, after].map(|index| index as u32));
+        next[before] = after;
+        previous[after] = before;
+        remaining -= 1;
+        misses = 0;
+        // Clipping an ear can only make its neighbours convex
+        reflex.retain(|&index| {
+            index != current
+                && !((index == before || index == after)
+                    && convex(previous[index], index, next[index]))
+        });
+        current = before;
+    }
+    indices.extend([previous[current], current, next[current]].map(|index| index as u32));
+    indices
+}
+
+// Triangles from the first corner to every other pair of neighbouring corners
+fn fan(indices: &mut Vec<u32>, first: usize, next: &[usize], count: usize) {
+    let mut corner = next[first];
+    for _ in 0..count - 2 {
+        indices.extend([first, corner, next[corner]].map(|index| index as u32));
+        corner = next[corner];
+    }
+}
+
+fn in_triangle(point: Vec2, a: Vec2, b: Vec2, c: Vec2) -> bool {
+    (b - a).perp_dot(point - a) >= 0.
+        && (c - b).perp_dot(point - b) >= 0.
+        && (a - c).perp_dot(point - c) >= 0.
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triangulated_area(points: &[Vec2]) -> f32 {
+        triangulate(points)
+            .chunks_exact(3)
+            .map(|triangle| {
+                let [a, b, c] = [0, 1, 2].map(|corner| points[triangle[corner] as usize]);
+                (b - a).perp_dot(c - a) / 2.
+            })
+            .sum()
+    }
+
+    fn square(center: Vec2, size: f32) -> Vec<Vec2> {
+        [vec2(-1., -1.), vec2(1., -1.), vec2(1., 1.), vec2(-1., 1.)]
+            .map(|corner| center + corner * size / 2.)
+            .to_vec()
+    }
+
+    #[test]
+    fn defaults_scale_with_units_per_pixel() {
+        assert_eq!(ShapeStyle::default().stroke_width, 2.);
+        assert_eq!(ShapeStyle::with_units_per_pixel(0.5).stroke_width, 1.);
+
+        let arrow = Arrow::with_units_per_pixel(vec2(0., 0.), vec2(10., 0.), 0.25);
+        assert_eq!((arrow.head_length, arrow.head_width), (4., 3.));
+        assert_eq!(arrow.style.stroke_width, 0.5);
+        assert_eq!(arrow.style.fill, None);
+    }
+
+    #[test]
+    fn triangulates_concave_polygons() {
+        // An L with its reflex corner at (1, 1)
+        let mut points = vec![
+            vec2(0., 0.),
+            vec2(2., 0.),
+            vec2(2., 1.),
+            vec2(1., 1.),
+            vec2(1., 2.),
+            vec2(0., 2.),
+        ];
+        assert_eq!(triangulate(&points).len(), 4 * 3);
+        assert!((triangulated_area(&points) - 3.).abs() < 1e-5);
+
+        // Triangles always come out counter clockwise
+        points.reverse();
+        assert!((triangulated_area(&points) - 3.).abs() < 1e-5);
+    }
+
+    #[test]
+    fn triangulates_combs() {
+        // A zigzag comb, every other corner is reflex
+        let teeth = 50;
+        let mut points: Vec<Vec2> = (0..=teeth * 2)
+            .map(|index| vec2(index as f32, if index % 2 == 0 { 1. } else { 3. }))
+            .collect();
+        points.extend([vec2(teeth as f32 * 2., 0.), vec2(0., 0.)]);
+        let area = signed_area(&points).abs();
+        assert_eq!(triangulate(&points).len(), (points.len() - 2) * 3);
+        assert!((triangulated_area(&points) - area).abs() < 1e-3);
+    }
+
+    #[test]
+    fn bridges_holes_into_their_contour() {
+        let outer = square(Vec2::ZERO, 10.);
+        let hole = square(vec2(1., 1.), 4.);
+        let island = square(vec2(1., 1.), 2.);
+        let polygons = contour_polygons(&[&outer, &hole, &island]);
+        assert_eq!(polygons.len(), 2);
+
+        let area: f32 = polygons
+            .iter()
+            .map(|polygon| triangulated_area(polygon))
+            .sum();
+        assert!((area - (100. - 16. + 4.)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn long_outlines_fill_in_several_meshes() {
+        let points: Vec<Vec2> = (0..4000)
+            .map(|index| Vec2::from_angle(index as f32 / 4000. * std::f32::consts::TAU) * 100.)
+            .collect();
+        let meshes = meshes(&points, &triangulate(&points), WHITE);
+        assert!(meshes.len() > 1);
+        assert_eq!(
+            meshes.iter().map(|mesh| mesh.indices.len()).sum::<usize>(),
+            3998 * 3
+        );
+        assert!(meshes
+            .iter()
+            .all(|mesh| mesh.indices.len() < 5000 && mesh.vertices.len() < 10000));
+    }
+}
@@ -0,0 +1,186 @@
+use macroquad::prelude::*;
+
+use crate::{error::Error, post::POST_VERTEX_SHADER};
+
+pub const MAX_SSAA_FACTOR: u32 = 4;
+
+#[derive(Default, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum DownsampleFilter {
+    // Averages the block of samples covering each output pixel
+    #[default]
+    Box,
+    // Lanczos with a 2 pixel radius, sharper than box but can ring slightly on hard edges
+    Lanczos,
+}
+
+pub(crate) struct Supersampler {
+    factor: u32,
+    material: Material,
+    target: Option<RenderTarget>,
+}
+
+impl Supersampler {
+    pub(crate) fn new(factor: u32, filter: DownsampleFilter) -> Result<Self, Error> {
+        if !(2..=MAX_SSAA_FACTOR).contains(&factor) {
+            return Err(Error::InvalidSsaaFactor(factor));
+        }
+
+        let fragment = match filter {
+            DownsampleFilter::Box => BOX_FRAGMENT_SHADER,
+            DownsampleFilter::Lanczos => LANCZOS_FRAGMENT_SHADER,
+        };
+        let material = load_material(
+            ShaderSource::Glsl {
+                vertex: POST_VERTEX_SHADER,
+                fragment,
+            },
+            MaterialParams {
+                uniforms: vec![
+                    ("source_size".to_string(), UniformType::Float2),
+                    ("factor".to_string(), UniformType::Float1),
+                ],
+                ..Default::default()
+            },
+        )
+        .map_err(|source| Error::Shader {
+            name: "ssaa".to_owned(),
+            source,
+        })?;
+
+        Ok(Self {
+            factor,
+            material,
+            target: None,
+        })
+    }
+
+    pub(crate) fn factor(&self) -> u32 {
+        self.factor
+    }
+
+    // Filters the supersampled texture down to a texture 1 / factor of its size
+    pub(crate) fn resolve(&mut self, source: &Texture2D, filter_mode: FilterMode) -> Texture2D {
+        let source_size = source.size();
+        let size = (source_size / self.factor as f32).floor();
+        if let Some(target) = self.target.take_if(|target| target.texture.size() != size) {
+            target.delete();
+        }
+        let target = self
+            .target
+            .get_or_insert_with(|| render_target(size.x as u32, size.y as u32));
+        target.texture.set_filter(filter_mode);
+
+        // Keeps the first row at the top, the same orientation as the source
+        set_camera(&Camera2D {
+            target: size / 2.,
+            zoom: vec2(2. / size.x, 2. / size.y),
+            render_target: Some(target.clone()),
+            ..Default::default()
+        });
+
+        self.material.set_uniform("source_size", source_size);
+        self.material.set_uniform("factor", self.factor as f32);
+        gl_use_material(&self.material);
+        draw_texture_ex(
+            source,
+            0.,
+            0.,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(size),
+                ..Default::default()
+            },
+        );
+        gl_use_default_material();
+
+        set_default_camera();
+        target.texture.clone()
+    }
+}
+
+// Turning supersampling off or changing the factor drops the supersampler, render targets aren't
+// freed when dropped
+impl Drop for Supersampler {
+    fn drop(&mut self) {
+        if let Some(target) = &self.target {
+            target.delete();
+        }
+    }
+}
+
+const BOX_FRAGMENT_SHADER: &str = r#"#version 100
+precision highp float;
+
+varying vec2 uv;
+
+uniform sampler2D Texture;
+uniform vec2 source_size;
+uniform float factor;
+
+const int MAX_FACTOR = 4;
+
+void main() {
+    // Top left source texel of the block covering this pixel
+    vec2 block = floor(uv * source_size / factor) * factor;
+
+    vec4 sum = vec4(0.0);
+    for (int x = 0; x < MAX_FACTOR; x++) {
+        if (float(x) >= factor) { break; }
+        for (int y = 0; y < MAX_FACTOR; y++) {
+            if (float(y) >= factor) { break; }
+            vec2 texel = block + vec2(float(x), float(y)) + 0.5;
+            sum += texture2D(Texture, texel / source_size);
+        }
+    }
+
+    gl_FragColor = sum / (factor * factor);
+}"#;
+
+const LANCZOS_FRAGMENT_SHADER: &str = r#"#version 100
+precision highp float;
+
+varying vec2 uv;
+
+uniform sampler2D Texture;
+uniform vec2 source_size;
+uniform float factor;
+
+const float PI = 3.14159265;
+const float RADIUS = 2.0;
+// RADIUS output pixels either side at the largest factor, smaller factors stop sooner
+const int MAX_TAPS = 8;
+
+float lanczos(float x) {
+    if (abs(x) < 0.0001) { return 1.0; }
+    if (abs(x) >= RADIUS) { return 0.0; }
+    float px = PI * x;
+    return RADIUS * sin(px) * sin(px / RADIUS) / (px * px);
+}
+
+void main() {
+    vec2 center = uv * source_size;
+    vec2 base = floor(center);
+
+    float taps = factor * RADIUS;
+    vec4 sum = vec4(0.0);
+    float total_weight = 0.0;
+    // Loops need constant bounds, so count up and break past the taps this factor needs
+    for (int i = 0; i <= 2 * MAX_TAPS; i++) {
+        float x = float(i) - taps;
+        if (x > taps) { break; }
+        for (int j = 0; j <= 2 * MAX_TAPS; j++) {
+            float y = float(j) - taps;
+            if (y > taps) { break; }
+            vec2 texel = base + vec2(x, y) + 0.5;
+            // Distance measured in output pixels
+            vec2 dist = (texel - center) / factor;
+            float weight = lanczos(dist.x) * lanczos(dist.y);
+            if (weight != 0.0) {
+                sum += texture2D(Texture, texel / source_size) * weight;
+                total_weight += weight;
+            }
+        }
+    }
+
+    gl_FragColor = clamp(sum / total_weight, 0.0, 1.0);
+}"#;
@@ -0,0 +1,664 @@
+use std::{
+    collections::HashMap,
+    f32::consts::{PI, TAU},
+};
+
+use crate::{tween::Lerp, Animation};
+
+use macroquad::{models::Vertex, prelude::*};
+
+// macroquad clamps every draw call to fewer than 10000 vertices and 5000 indices, so meshes are
+// kept below both
+const MAX_MESH_VERTICES: usize = 9999;
+const MAX_MESH_INDICES: usize = 4998;
+// Largest angle between two points of a round join or cap
+const ROUND_STEP: f32 = PI / 16.;
+// Joins turning less than this are drawn as straight
+const STRAIGHT_EPSILON: f32 = 1e-4;
+
+#[derive(Default, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum LineJoin {
+    // Extends the edges until they meet, bevelled past the miter limit
+    #[default]
+    Miter,
+    Round,
+    Bevel,
+}
+
+#[derive(Default, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum LineCap {
+    // Ends exactly at the end points
+    #[default]
+    Butt,
+    Round,
+    // Extends past the end points by half the width
+    Square,
+}
+
+#[derive(Clone, Debug)]
+pub struct StrokeStyle {
+    pub width: f32,
+    pub join: LineJoin,
+    pub cap: LineCap, // Every dash gets the caps
+    // How far a miter may reach from the joint as a multiple of half the width
+    pub miter_limit: f32,
+    pub dash: Option<DashPattern>, // None for a solid line
+}
+
+// 2 units wide, 2 logical pixels at the default world size
+impl Default for StrokeStyle {
+    fn default() -> Self {
+        Self::new(2.)
+    }
+}
+
+impl StrokeStyle {
+    pub fn new(width: f32) -> Self {
+        Self {
+            width,
+            join: LineJoin::default(),
+            cap: LineCap::default(),
+            miter_limit: 4.,
+            dash: None,
+        }
+    }
+
+    // The default style 2 logical pixels wide in the animation's world units
+    pub fn for_animation(animation: &Animation) -> Self {
+        Self::new(animation.pixels_to_units(2.))
+    }
+
+    pub fn join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        self
+    }
+
+    pub fn cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    pub fn miter_limit(mut self, miter_limit: f32) -> Self {
+        self.miter_limit = miter_limit;
+        self
+    }
+
+    pub fn dash(mut self, dash: DashPattern) -> Self {
+        self.dash = Some(dash);
+        self
+    }
+}
+
+// Alternating dash and gap lengths in world units, measured along the line so dashes stay even
+// around curves. A dash of length 0 is drawn as a dot as wide as the line.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DashPattern {
+    lengths: Vec<f32>,
+    offset: f32,
+}
+
+impl DashPattern {
+    // An odd number of lengths is repeated, so [4, 2, 1] means dash 4, gap 2, dash 1, gap 4 and so
+    // on
+    pub fn new(lengths: &[f32]) -> Self {
+        let mut lengths: Vec<f32> = lengths.iter().map(|length| length.max(0.)).collect();
+        if lengths.len() % 2 == 1 {
+            lengths.extend_from_within(..);
+        }
+        Self {
+            lengths,
+            offset: 0.,
+        }
+    }
+
+    pub fn dashed(dash: f32, gap: f32) -> Self {
+        Self::new(&[dash, gap])
+    }
+
+    // Dots the width of the line, spacing apart
+    pub fn dotted(spacing: f32) -> Self {
+        Self::new(&[0., spacing])
+    }
+
+    pub fn dash_dot(dash: f32, gap: f32) -> Self {
+        Self::new(&[dash, gap, 0., gap])
+    }
+
+    // Moves the pattern forwards along the line, animating it makes the dashes march
+    pub fn offset(mut self, offset: f32) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn set_offset(&mut self, offset: f32) {
+        self.offset = offset;
+    }
+
+    pub fn lengths(&self) -> &[f32] {
+        &self.lengths
+    }
+
+    // The pieces of an open polyline covered by dashes
+    fn split(&self, points: &[Vec2]) -> Vec<Vec<Vec2>> {
+        let total: f32 = self.lengths.iter().sum();
+        if points.is_empty() {
+            return Vec::new();
+        }
+        if total <= 0. {
+            return vec![points.to_vec()];
+        }
+
+        // Where in the pattern the line starts
+        let mut index = 0;
+        let mut phase = (-self.offset).rem_euclid(total);
+        while phase > self.lengths[index] {
+            phase -= self.lengths[index];
+            index = (index + 1) % self.lengths.len();
+        }
+        let mut remaining = self.lengths[index] - phase;
+
+        let mut pieces = Vec::new();
+        let mut current = Vec::new();
+        if index % 2 == 0 {
+            current.push(points[0]);
+        }
+        for pair in points.windows(2) {
+            let length = pair[0].distance(pair[1]);
+            let mut travelled = 0.;
+            while length - travelled > remaining {
+                travelled += remaining;
+                let point = pair[0].lerp(pair[1], travelled / length);
+                if index % 2 == 0 {
+                    current.push(point);
+                    self.finish_piece(&mut pieces, std::mem::take(&mut current), index);
+                }
+                index = (index + 1) % self.lengths.len();
+                remaining = self.lengths[index];
+                if index % 2 == 0 {
+                    current.push(point);
+                }
+            }
+            remaining -= length - travelled;
+            if index % 2 == 0 {
+                current.push(pair[1]);
+            }
+        }
+        // The line can end partway through a dash
+        if index % 2 == 0 {
+            self.finish_piece(&mut pieces, current, index);
+        }
+        pieces
+    }
+
+    // Only dashes meant to be dots end where they started
+    fn finish_piece(&self, pieces: &mut Vec<Vec<Vec2>>, piece: Vec<Vec2>, index: usize) {
+        if piece.len() > 1
+            && (self.lengths[index] == 0. || piece.windows(2).any(|pair| pair[0] != pair[1]))
+        {
+            pieces.push(piece);
+        }
+    }
+}
+
+// Patterns with different numbers of lengths switch halfway
+impl Lerp for DashPattern {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        if self.lengths.len() != other.lengths.len() {
+            return if t < 0.5 { self.clone() } else { other.clone() };
+        }
+        Self {
+            lengths: self
+                .lengths
+                .iter()
+                .zip(&other.lengths)
+                .map(|(from, to)| from.lerp(to, t))
+                .collect(),
+            offset: self.offset.lerp(&other.offset, t),
+        }
+    }
+}
+
+pub fn draw_polyline(points: &[Vec2], closed: bool, style: &StrokeStyle, color: Color) {
+    for mesh in stroke(points, closed, style, color) {
+        draw_mesh(&mesh);
+    }
+}
+
+// Tessellates a thick polyline into triangles. The segments and joins don't overlap, so
+// translucent lines keep an even colour, except at joins sharper than the segments around them
+// are long. Polylines longer than a few hundred points need more than one mesh.
+pub fn stroke(points: &[Vec2], closed: bool, style: &StrokeStyle, color: Color) -> Vec<Mesh> {
+    if style.width <= 0. {
+        return Vec::new();
+    }
+
+    let mut stroker = Stroker {
+        half_width: style.width / 2.,
+        style,
+        vertices: Vec::new(),
+        indices: Vec::new(),
+        lookup: HashMap::new(),
+    };
+    match &style.dash {
+        Some(dash) => {
+            let mut points = points.to_vec();
+            if closed {
+                points.extend(points.first().copied());
+            }
+            for piece in dash.split(&points) {
+                let Some(&first) = piece.first() else {
+                    continue;
+                };
+                // Zero length dashes are dots whatever the cap
+                if piece.windows(2).all(|pair| pair[0] == pair[1]) {
+                    stroker.fan(first, first, vec2(stroker.half_width, 0.), TAU);
+                } else {
+                    stroker.polyline(&piece, false);
+                }
+            }
+        }
+        None => stroker.polyline(points, closed),
+    }
+
+    meshes(&stroker.vertices, &stroker.indices, color)
+}
+
+struct Stroker<'a> {
+    half_width: f32,
+    style: &'a StrokeStyle,
+    vertices: Vec<Vec2>,
+    indices: Vec<u32>,
+    // Segments and joins meet at the same points, which share a vertex
+    lookup: HashMap<[u32; 2], u32>,
+}
+
+impl Stroker<'_> {
+    fn polyline(&mut self, points: &[Vec2], closed: bool) {
+        let mut points = points.to_vec();
+        points.dedup();
+        if closed && points.len() > 2 && points.first() == points.last() {
+            points.pop();
+        }
+        if points.len() < 2 {
+            return;
+        }
+        let count = points.len();
+
+        // The left and right edges where each point ends the segment coming in and starts the one
+        // going out, in the direction of travel
+        let mut ends = Vec::with_capacity(count);
+        let mut starts = Vec::with_capacity(count);
+        for index in 0..count {
+            let previous = match index {
+                0 if closed => Some(points[count - 1]),
+                0 => None,
+                _ => Some(points[index - 1]),
+            };
+            let next = match index + 1 {
+                next if next < count => Some(points[next]),
+                _ if closed => Some(points[0]),
+                _ => None,
+            };
+
+            let point = points[index];
+            let (end, start) = match (previous, next) {
+                (Some(previous), Some(next)) => self.join(
+                    point,
+                    (point - previous).normalize(),
+                    (next - point).normalize(),
+                ),
+                // Caps face away from the line, so their left and right swap at the start
+                (None, Some(next)) => {
+                    let edges = self.cap(point, (point - next).normalize());
+                    (edges, (edges.1, edges.0))
+                }
+                (Some(previous), None) => {
+                    let edges = self.cap(point, (point - previous).normalize());
+                    (edges, edges)
+                }
+                (None, None) => unreachable!(),
+            };
+            ends.push(end);
+            starts.push(start);
+        }
+
+        let segments = if closed { count } else { count - 1 };
+        for index in 0..segments {
+            let (start_left, start_right) = starts[index];
+            let (end_left, end_right) = ends[(index + 1) % count];
+            self.triangle(start_left, start_right, end_right);
+            self.triangle(start_left, end_right, end_left);
+        }
+    }
+
+    fn triangle(&mut self, a: Vec2, b: Vec2, c: Vec2) {
+        for point in [a, b, c] {
+            let vertices = &mut self.vertices;
+            let index = *self
+                .lookup
+                .entry(point.to_array().map(f32::to_bits))
+                .or_insert_with(|| {
+                    vertices.push(point);
+                    vertices.len() as u32 - 1
+                });
+            self.indices.push(index);
+        }
+    }
+
+    // Fans around the centre along an arc starting at the offset from the arc centre, the sweep is in
+    // radians counter clockwise
+    fn fan(&mut self, fan_center: Vec2, arc_center: Vec2, from: Vec2, sweep: f32) {
+        let steps = (sweep.abs() / ROUND_STEP).ceil().max(1.) as usize;
+        let mut previous = arc_center + from;
+        for step in 1..=steps {
+            let angle = sweep * step as f32 / steps as f32;
+            let next = arc_center + Vec2::from_angle(angle).rotate(from);
+            self.triangle(fan_center, previous, next);
+            previous = next;
+        }
+    }
+
+    // The edges at an end point, pointing away from the line, as (left, right) looking outwards
+    fn cap(&mut self, point: Vec2, outwards: Vec2) -> (Vec2, Vec2) {
+        let side = outwards.perp() * self.half_width;
+        match self.style.cap {
+            LineCap::Butt => (point + side, point - side),
+            LineCap::Square => {
+                let point = point + outwards * self.half_width;
+                (point + side, point - side)
+            }
+            LineCap::Round => {
+                self.fan(point, point, side, -PI);
+                (point + side, point - side)
+            }
+        }
+    }
+
+    // The edges where a joint ends the incoming segment and starts the outgoing one. The inner
+    // side meets at a single point and the gap on the outer side is filled by the join.
+    fn join(
+        &mut self,
+        point: Vec2,
+        incoming: Vec2,
+        outgoing: Vec2,
+    ) -> ((Vec2, Vec2), (Vec2, Vec2)) {
+        let (incoming_normal, outgoing_normal) = (incoming.perp(), outgoing.perp());
+        let turn = incoming.perp_dot(outgoing);
+        let straight = (
+            point + incoming_normal * self.half_width,
+            point - incoming_normal * self.half_width,
+        );
+
+        if turn.abs() < STRAIGHT_EPSILON {
+            if incoming.dot(outgoing) > 0. {
+                return (straight, straight);
+            }
+            // Doubling straight back, only a round join has anything to fill
+            if self.style.join == LineJoin::Round {
+                self.fan(point, point, incoming_normal * self.half_width, -PI);
+            }
+            return (straight, (straight.1, straight.0));
+        }
+
+        // Left turns have their outer side on the right
+        let outer = if turn > 0. { -1. } else { 1. };
+        let miter = (incoming_normal + outgoing_normal).normalize();
+        let cos = miter.dot(incoming_normal);
+        let inner = point - miter * outer * self.half_width / cos;
+        let outer_in = point + incoming_normal * outer * self.half_width;
+        let outer_out = point + outgoing_normal * outer * self.half_width;
+
+        match self.style.join {
+            LineJoin::Miter if 1. / cos <= self.style.miter_limit => {
+                let tip = point + miter * outer * self.half_width / cos;
+                self.triangle(inner, outer_in, tip);
+                self.triangle(inner, tip, outer_out);
+            }
+            LineJoin::Round => {
+                let sweep = incoming_normal.angle_between(outgoing_normal);
+                self.fan(
+                    inner,
+                    point,
+                    incoming_normal * outer * self.half_width,
+                    sweep,
+                );
+            }
+            _ => self.triangle(inner, outer_in, outer_out),
+        }
+
+        if turn > 0. {
+            ((inner, outer_in), (inner, outer_out))
+        } else {
+            ((outer_in, inner), (outer_out, inner))
+        }
+    }
+}
+
+// Splits indexed triangles into meshes small enough for a single draw call each
+pub(crate) fn meshes(vertices: &[Vec2], indices: &[u32], color: Color) -> Vec<Mesh> {
+    let vertex = |point: Vec2| Vertex {
+        position: point.extend(0.),
+        uv: vec2(0., 0.),
+        color,
+    };
+
+    let mut meshes = Vec::new();
+    let mut mesh = Mesh {
+        vertices: Vec::new(),
+        indices: Vec::new(),
+        texture: None,
+    };
+    // Where each vertex is in the current mesh
+    let mut local: HashMap<u32, u16> = HashMap::new();
+    for triangle in indices.chunks_exact(3) {
+        let new = triangle
+            .iter()
+            .filter(|index| !local.contains_key(index))
+            .count();
+        if mesh.indices.len() + 3 > MAX_MESH_INDICES
+            || mesh.vertices.len() + new > MAX_MESH_VERTICES
+        {
+            meshes.push(std::mem::replace(
+                &mut mesh,
+                Mesh {
+                    vertices: Vec::new(),
+                    indices: Vec::new(),
+                    texture: None,
+                },
+            ));
+            local.clear();
+        }
+
+        for &index in triangle {
+            let local_index = *local.entry(index).or_insert_with(|| {
+                mesh.vertices.push(vertex(vertices[index as usize]));
+                mesh.vertices.len() as u16 - 1
+            });
+            mesh.indices.push(local_index);
+        }
+    }
+    if !mesh.indices.is_empty() {
+        meshes.push(mesh);
+    }
+    meshes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area(meshes: &[Mesh]) -> f32 {
+        meshes
+            .iter()
+            .flat_map(|mesh| {
+                mesh.indices.chunks_exact(3).map(|triangle| {
+                    let [a, b, c] =
+                        [0, 1, 2].map(|corner| mesh.vertices[triangle[corner] as usize].position);
+                    (b - a).truncate().perp_dot((c - a).truncate()).abs() / 2.
+                })
+            })
+            .sum()
+    }
+
+    fn corner(join: LineJoin) -> f32 {
+        let points = [vec2(0., 0.), vec2(10., 0.), vec2(10., 10.)];
+        area(&stroke(
+            &points,
+            false,
+            &StrokeStyle::new(2.).join(join),
+            WHITE,
+        ))
+    }
+
+    fn piece_lengths(dash: &DashPattern, points: &[Vec2]) -> Vec<f32> {
+        dash.split(points)
+            .iter()
+            .map(|piece| piece.windows(2).map(|pair| pair[0].distance(pair[1])).sum())
+            .collect()
+    }
+
+    fn assert_lengths(lengths: &[f32], expected: &[f32]) {
+        assert_eq!(lengths.len(), expected.len(), "{lengths:?}");
+        for (length, expected) in lengths.iter().zip(expected) {
+            assert!((length - expected).abs() < 1e-4, "{lengths:?}");
+        }
+    }
+
+    #[test]
+    fn dashes_follow_the_pattern_around_corners() {
+        let points = [vec2(0., 0.), vec2(5., 0.), vec2(5., 5.)];
+        let dash = DashPattern::dashed(2., 1.);
+        assert_lengths(&piece_lengths(&dash, &points), &[2., 2., 2., 1.]);
+
+        let mut pieces = dash.split(&points);
+        pieces.iter_mut().for_each(|piece| piece.dedup());
+        assert_eq!(pieces[1], vec![vec2(3., 0.), vec2(5., 0.)]);
+        assert_eq!(pieces[2], vec![vec2(5., 1.), vec2(5., 3.)]);
+        // Dashes go round corners
+        assert_eq!(
+            dash.split(&[vec2(0., 0.), vec2(4., 0.), vec2(4., 4.)])[1],
+            vec![vec2(3., 0.), vec2(4., 0.), vec2(4., 1.)]
+        );
+    }
+
+    #[test]
+    fn offsets_shift_the_pattern_back() {
+        let points = [vec2(0., 0.), vec2(10., 0.)];
+        let dash = DashPattern::dashed(2., 2.).offset(1.);
+        assert_lengths(&piece_lengths(&dash, &points), &[2., 2., 1.]);
+        assert_eq!(dash.split(&points)[0], vec![vec2(1., 0.), vec2(3., 0.)]);
+
+        // Offsets wrap around the pattern
+        let dash = DashPattern::dashed(2., 2.).offset(1.);
+        assert_eq!(dash.split(&points), dash.clone().offset(-3.).split(&points));
+        assert_eq!(dash.split(&points), dash.clone().offset(9.).split(&points));
+    }
+
+    #[test]
+    fn odd_patterns_repeat() {
+        assert_eq!(
+            DashPattern::new(&[4., 2., 1.]).lengths(),
+            &[4., 2., 1., 4., 2., 1.]
+        );
+        assert_eq!(DashPattern::new(&[3., -1.]).lengths(), &[3., 0.]);
+    }
+
+    #[test]
+    fn dots_are_zero_length_pieces() {
+        let points = [vec2(0., 0.), vec2(10., 0.)];
+        let pieces = DashPattern::dotted(5.).split(&points);
+        let dots: Vec<Vec2> = pieces.iter().map(|piece| piece[0]).collect();
+        // The last gap ends exactly at the end, the next dot is past it
+        assert_eq!(dots, vec![vec2(0., 0.), vec2(5., 0.)]);
+        assert!(pieces
+            .iter()
+            .all(|piece| piece.windows(2).all(|pair| pair[0] == pair[1])));
+
+        let dash_dot = DashPattern::dash_dot(3., 1.);
+        assert_lengths(&piece_lengths(&dash_dot, &points), &[3., 0., 3., 0.]);
+    }
+
+    #[test]
+    fn zero_length_lines_have_no_dashes() {
+        let point = vec2(1., 1.);
+        assert!(DashPattern::dashed(2., 1.)
+            .split(&[point, point])
+            .is_empty());
+        let meshes = stroke(
+            &[point, point],
+            false,
+            &StrokeStyle::new(2.).dash(DashPattern::dashed(2., 1.)),
+            WHITE,
+        );
+        assert!(meshes.is_empty());
+    }
+
+    #[test]
+    fn empty_lines_have_no_dashes() {
+        let dash = DashPattern::dashed(2., 1.);
+        assert!(dash.split(&[]).is_empty());
+        assert!(DashPattern::new(&[]).split(&[]).is_empty());
+        for closed in [false, true] {
+            let style = StrokeStyle::new(2.).dash(dash.clone());
+            assert!(stroke(&[], closed, &style, WHITE).is_empty());
+        }
+    }
+
+    #[test]
+    fn long_polylines_fit_in_draw_calls() {
+        let points: Vec<Vec2> = (0..5000)
+            .map(|index| vec2(index as f32, (index as f32 * 0.3).sin() * 50.))
+            .collect();
+        for join in [LineJoin::Miter, LineJoin::Round, LineJoin::Bevel] {
+            let meshes = stroke(&points, false, &StrokeStyle::new(20.).join(join), WHITE);
+            assert!(meshes.len() > 1);
+            for mesh in &meshes {
+                assert!(mesh.indices.len() < 5000);
+                assert!(mesh.vertices.len() < 10000);
+                assert!(mesh
+                    .indices
+                    .iter()
+                    .all(|&index| (index as usize) < mesh.vertices.len()));
+            }
+        }
+    }
+
+    #[test]
+    fn segments_share_vertices() {
+        let points: Vec<Vec2> = (0..100).map(|index| vec2(index as f32, 0.)).collect();
+        let meshes = stroke(&points, false, &StrokeStyle::new(2.), WHITE);
+        assert_eq!(meshes.len(), 1);
+        assert_eq!(meshes[0].vertices.len(), 200);
+    }
+
+    #[test]
+    fn joins_cover_the_corner_once() {
+        assert!((corner(LineJoin::Miter) - 40.).abs() < 1e-3);
+        assert!((corner(LineJoin::Bevel) - 39.5).abs() < 1e-3);
+        let round = 40. - 1. + PI / 4.;
+        assert!((corner(LineJoin::Round) - round).abs() < 0.01);
+    }
+
+    #[test]
+    fn closed_polylines_have_no_ends() {
+        let square = [vec2(0., 0.), vec2(10., 0.), vec2(10., 10.), vec2(0., 10.)];
+        let meshes = stroke(&square, true, &StrokeStyle::new(2.), WHITE);
+        assert!((area(&meshes) - 80.).abs() < 1e-3);
+    }
+
+    #[test]
+    fn caps_extend_the_ends() {
+        let points = [vec2(0., 0.), vec2(10., 0.)];
+        let cap = |cap| {
+            area(&stroke(
+                &points,
+                false,
+                &StrokeStyle::new(2.).cap(cap),
+                WHITE,
+            ))
+        };
+        assert!((cap(LineCap::Butt) - 20.).abs() < 1e-3);
+        assert!((cap(LineCap::Square) - 24.).abs() < 1e-3);
+        assert!((cap(LineCap::Round) - (20. + PI)).abs() < 0.05);
+    }
+}
@@ -0,0 +1,211 @@
+use std::ops::Range;
+
+use macroquad::prelude::*;
+
+use crate::easing::Easing;
+
+pub trait Lerp {
+    fn lerp(&self, other: &Self, t: f32) -> Self;
+
+    // Lerps between the same two values for every t, used by tween_to. Types with costly lerps
+    // prepare the work once here.
+    fn lerper(&self, other: &Self) -> Box<dyn Fn(f32) -> Self>
+    where
+        Self: Clone + 'static,
+    {
+        let (from, to) = (self.clone(), other.clone());
+        Box::new(move |t| from.lerp(&to, t))
+    }
+}
+
+impl Lerp for f32 {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Lerp for Vec2 {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Vec2::lerp(*self, *other, t)
+    }
+}
+
+impl Lerp for Color {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Color {
+            r: self.r.lerp(&other.r, t),
+            g: self.g.lerp(&other.g, t),
+            b: self.b.lerp(&other.b, t),
+            a: self.a.lerp(&other.a, t),
+        }
+    }
+}
+
+impl Lerp for Range<f32> {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        self.start.lerp(&other.start, t)..self.end.lerp(&other.end, t)
+    }
+}
+
+#[derive(Default, Copy, Clone)]
+pub enum Repeat {
+    #[default]
+    Never,
+    Times(u32), // Number of extra plays after the first
+    Forever,
+}
+
+#[derive(Clone)]
+pub struct Tween<T> {
+    from: T,
+    to: T,
+    duration: f32,
+    delay: f32,
+    easing: Easing,
+    repeat: Repeat,
+    yoyo: bool,
+}
+
+impl<T: Lerp + Clone> Tween<T> {
+    pub fn new(from: T, to: T, duration: f32) -> Self {
+        Self {
+            from,
+            to,
+            duration,
+            delay: 0.,
+            easing: Easing::default(),
+            repeat: Repeat::default(),
+            yoyo: false,
+        }
+    }
+
+    pub fn delay(mut self, delay: f32) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    pub fn repeat(mut self, repeat: Repeat) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    // Every second repeat plays backwards from `to` to `from`
+    pub fn yoyo(mut self, yoyo: bool) -> Self {
+        self.yoyo = yoyo;
+        self
+    }
+
+    pub fn from(&self) -> &T {
+        &self.from
+    }
+
+    pub fn to(&self) -> &T {
+        &self.to
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    // The time from zero until the tween settles, including the delay. None if it repeats forever.
+    pub fn total_duration(&self) -> Option<f32> {
+        match self.repeat {
+            Repeat::Never => Some(self.delay + self.duration),
+            Repeat::Times(times) => Some(self.delay + self.duration * (times + 1) as f32),
+            Repeat::Forever => None,
+        }
+    }
+
+    pub fn is_finished(&self, time: f32) -> bool {
+        matches!(self.total_duration(), Some(total) if time >= total)
+    }
+
+    // Progress through the current play after easing and yoyo are applied
+    pub fn progress_at(&self, time: f32) -> f32 {
+        let local_time = time - self.delay;
+        if local_time <= 0. {
+            return self.easing.ease(0.);
+        }
+
+        let plays = match self.repeat {
+            Repeat::Never => Some(1),
+            Repeat::Times(times) => Some(times + 1),
+            Repeat::Forever => None,
+        };
+
+        let (play, t) = if self.duration <= 0. {
+            (plays.map_or(0, |plays| plays - 1), 1.)
+        } else {
+            let elapsed = local_time / self.duration;
+            match plays {
+                Some(plays) if elapsed >= plays as f32 => (plays - 1, 1.),
+                _ => (elapsed.floor() as u32, elapsed.fract()),
+            }
+        };
+
+        let t = if self.yoyo && play % 2 == 1 {
+            1. - t
+        } else {
+            t
+        };
+        self.easing.ease(t)
+    }
+
+    pub fn value_at(&self, time: f32) -> T {
+        self.from.lerp(&self.to, self.progress_at(time))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plays_once_after_the_delay() {
+        let tween = Tween::new(10., 20., 2.).delay(1.);
+        assert_eq!(tween.value_at(0.), 10.);
+        assert_eq!(tween.value_at(1.), 10.);
+        assert_eq!(tween.value_at(2.), 15.);
+        assert_eq!(tween.value_at(5.), 20.);
+        assert_eq!(tween.total_duration(), Some(3.));
+        assert!(!tween.is_finished(2.9));
+        assert!(tween.is_finished(3.));
+    }
+
+    #[test]
+    fn repeats_and_yoyos() {
+        let tween = Tween::new(0., 1., 1.).repeat(Repeat::Times(2)).yoyo(true);
+        assert_eq!(tween.total_duration(), Some(3.));
+        assert_eq!(tween.value_at(0.25), 0.25);
+        // The second play runs backwards
+        assert_eq!(tween.value_at(1.25), 0.75);
+        assert_eq!(tween.value_at(2.25), 0.25);
+        // Settles where the last play ends
+        assert_eq!(tween.value_at(10.), 1.);
+
+        let forever = Tween::new(0., 1., 1.).repeat(Repeat::Forever);
+        assert_eq!(forever.total_duration(), None);
+        assert!(!forever.is_finished(1000.));
+        assert_eq!(forever.value_at(100.5), 0.5);
+    }
+
+    #[test]
+    fn zero_durations_jump_to_the_end() {
+        let tween = Tween::new(vec2(0., 0.), vec2(2., 4.), 0.);
+        assert_eq!(tween.value_at(0.5), vec2(2., 4.));
+        let yoyo = Tween::new(0., 1., 0.).repeat(Repeat::Times(1)).yoyo(true);
+        assert_eq!(yoyo.value_at(1.), 0.);
+    }
+
+    #[test]
+    fn colors_and_ranges_lerp_per_component() {
+        let color = Color::new(0., 0.5, 1., 0.).lerp(&Color::new(1., 0.5, 0., 1.), 0.25);
+        assert_eq!(color, Color::new(0.25, 0.5, 0.75, 0.25));
+        assert_eq!((0f32..10.).lerp(&(10.0..30.), 0.5), 5.0..20.);
+    }
+}
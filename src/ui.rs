@@ -1,21 +1,38 @@
-use crate::DEFAULT_FONT;
+use crate::{
+    font::{get_font, FontHandle},
+    text_scale,
+};
 
 use super::map;
 use macroquad::prelude::*;
 use std::ops::Range;
 
-pub fn draw_text_centered(text: &str, x: f32, y: f32, font_size: u16, color: Color) {
-    let text_center = get_text_center(text, DEFAULT_FONT.get(), font_size, 1., 0.);
+// A font of None draws with the default font
+pub fn draw_text_centered(
+    text: &str,
+    x: f32,
+    y: f32,
+    font_size: u16,
+    color: Color,
+    font: Option<FontHandle>,
+) {
+    let font = get_font(font);
+    // Rasterized at the resolution of the render target and scaled to font_size logical pixels in
+    // world units
+    let text_scale = text_scale();
+    let raster_size = (font_size as f32 * text_scale.raster_scale).round().max(1.) as u16;
+    let font_scale = font_size as f32 * text_scale.units_per_pixel / raster_size as f32;
+    let text_center = get_text_center(text, font.as_ref(), raster_size, font_scale, 0.);
     draw_text_ex(
         text,
         x - text_center.x,
         y + text_center.y,
         TextParams {
-            font_size,
-            font_scale: -1.,
+            font_size: raster_size,
+            font_scale: -font_scale,
             font_scale_aspect: -1.,
             color,
-            font: DEFAULT_FONT.get(),
+            font: font.as_ref(),
             ..Default::default()
         },
     );
@@ -168,6 +185,33 @@ impl Default for ButtonStyle {
     }
 }
 
+pub struct ButtonLabel {
+    pub text: String,
+    pub font_size: u16,
+    pub color: Color,
+    pub font: Option<FontHandle>, // None uses the default font
+}
+
+impl ButtonLabel {
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: text.to_owned(),
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for ButtonLabel {
+    fn default() -> Self {
+        Self {
+            text: String::new(),
+            font_size: 16,
+            color: WHITE,
+            font: None,
+        }
+    }
+}
+
 #[derive(Default)]
 pub enum ButtonType {
     Push,
@@ -180,6 +224,7 @@ pub struct Button {
     pub shape: ButtonShape,
     pub center_pos: Vec2,
     pub button_type: ButtonType,
+    pub label: Option<ButtonLabel>,
     mouse_pos: Option<Vec2>,
 }
 
@@ -190,6 +235,7 @@ impl Button {
             shape: button_shape,
             style: ButtonStyle::default(),
             button_type: ButtonType::default(),
+            label: None,
             mouse_pos: None,
         }
     }
@@ -204,6 +250,11 @@ impl Button {
         self
     }
 
+    pub fn label(mut self, label: ButtonLabel) -> Self {
+        self.label = Some(label);
+        self
+    }
+
     pub fn mouse_pos(mut self, mouse_pos: Vec2) -> Self {
         self.mouse_pos = Some(mouse_pos);
         self
@@ -262,5 +313,16 @@ impl Button {
                 );
             }
         }
+
+        if let Some(label) = &self.label {
+            draw_text_centered(
+                &label.text,
+                self.center_pos.x,
+                self.center_pos.y,
+                label.font_size,
+                label.color,
+                label.font,
+            );
+        }
     }
 }